@@ -0,0 +1,59 @@
+use std::fmt;
+
+// The groups of natives that interact with the world outside the interpreter. Embedders decide
+// which of them a script may use, all other natives are always available. There are no network
+// natives, so there is no capability for them.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Capability {
+    // reading environment variables
+    Env,
+    // reading and writing files, and importing modules
+    Fs,
+    // the command-line arguments and stdin of the process
+    Process,
+    // random numbers
+    Random,
+    // the current time
+    Time,
+}
+
+impl Capability {
+    pub const ALL: [Self; 5] = [Self::Env, Self::Fs, Self::Process, Self::Random, Self::Time];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Env => "env",
+            Self::Fs => "fs",
+            Self::Process => "process",
+            Self::Random => "random",
+            Self::Time => "time",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|capability| capability.name() == name)
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        for capability in Capability::ALL {
+            assert_eq!(Some(capability), Capability::from_name(capability.name()));
+        }
+        assert_eq!(None, Capability::from_name("net"));
+    }
+}
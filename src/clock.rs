@@ -1,5 +1,7 @@
-use std::time::SystemTime;
+use std::any::Any;
 
+use crate::capability::Capability;
+use crate::error::RuntimeError;
 use crate::interpreter::Interpreter;
 use crate::lox_callable::LoxCallable;
 use crate::value::Value;
@@ -19,11 +21,22 @@ impl LoxCallable for Clock {
     }
 
     // Returns the seconds since 1970-01-01
-    fn call(&self, _: &mut Interpreter, _: Vec<Value>) -> Value {
-        let since_epoch = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Time went backwards");
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        _: Vec<Value>,
+        line: usize,
+    ) -> Result<Value, RuntimeError> {
+        interpreter.require(Capability::Time, self.name(), line)?;
 
-        Value::Number(since_epoch.as_secs_f64())
+        Ok(Value::Number(interpreter.time_source().now()))
+    }
+
+    fn name(&self) -> &'static str {
+        "clock"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 }
@@ -0,0 +1,362 @@
+// Conversions between Rust values and Lox values, for programs embedding the interpreter
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::rc::Rc;
+
+use crate::lox_map::{LoxMap, MapKey};
+use crate::value::Value;
+
+// The error of a failed conversion from a Lox value to a Rust value
+#[derive(Debug, Eq, PartialEq)]
+pub struct ConversionError {
+    expected: &'static str,
+    found: String,
+}
+
+impl ConversionError {
+    fn new(expected: &'static str, found: &Value) -> Self {
+        let found = match found {
+            Value::String(string) => format!("string \"{string}\""),
+            Value::Nil => "nil".to_string(),
+            value => format!("{} '{value}'", value.type_name()),
+        };
+
+        Self { expected, found }
+    }
+}
+
+impl Error for ConversionError {}
+
+// The conversion of a Value to itself can't fail
+impl From<Infallible> for ConversionError {
+    fn from(infallible: Infallible) -> Self {
+        match infallible {}
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Expected {} but got {}", self.expected, self.found)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(bool: bool) -> Self {
+        Self::Bool(bool)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(number: f64) -> Self {
+        Self::Number(number)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(number: i32) -> Self {
+        Self::Number(f64::from(number))
+    }
+}
+
+impl From<u32> for Value {
+    fn from(number: u32) -> Self {
+        Self::Number(f64::from(number))
+    }
+}
+
+// Integers with more than 53 bits lose precision, as Lox numbers are f64
+impl From<i64> for Value {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(number: i64) -> Self {
+        Self::Number(number as f64)
+    }
+}
+
+impl From<usize> for Value {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(number: usize) -> Self {
+        Self::Number(number as f64)
+    }
+}
+
+impl From<String> for Value {
+    fn from(string: String) -> Self {
+        Self::String(string)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(string: &str) -> Self {
+        Self::String(string.to_string())
+    }
+}
+
+// None becomes nil
+impl<T: Into<Self>> From<Option<T>> for Value {
+    fn from(option: Option<T>) -> Self {
+        option.map_or(Self::Nil, Into::into)
+    }
+}
+
+impl<T: Into<Self>> From<Vec<T>> for Value {
+    fn from(elements: Vec<T>) -> Self {
+        let elements = elements.into_iter().map(Into::into).collect();
+        Self::List(Rc::new(RefCell::new(elements)))
+    }
+}
+
+// The keys are inserted in sorted order, as a HashMap has no order
+impl<T: Into<Self>, S> From<HashMap<String, T, S>> for Value {
+    fn from(entries: HashMap<String, T, S>) -> Self {
+        let mut entries: Vec<(String, T)> = entries.into_iter().collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut map = LoxMap::new();
+        for (key, value) in entries {
+            map.insert(MapKey::String(key), value.into());
+        }
+
+        Self::Map(Rc::new(RefCell::new(map)))
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bool(bool) => Ok(bool),
+            _ => Err(ConversionError::new("a bool", &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(number) => Ok(number),
+            _ => Err(ConversionError::new("a number", &value)),
+        }
+    }
+}
+
+// Only numbers without a fractional part that fit into the integer type can be converted
+macro_rules! impl_try_from_for_integer {
+    ($type:ty, $expected:literal) => {
+        impl TryFrom<Value> for $type {
+            type Error = ConversionError;
+
+            #[allow(
+                clippy::cast_lossless,
+                clippy::cast_possible_truncation,
+                clippy::cast_precision_loss,
+                clippy::cast_sign_loss
+            )]
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                // MAX + 1 is the exclusive upper bound, MAX of the 64 bit types already rounds
+                // up to it as f64
+                match value {
+                    Value::Number(number)
+                        if number.fract() == 0.0
+                            && number >= Self::MIN as f64
+                            && number < Self::MAX as f64 + 1.0 =>
+                    {
+                        Ok(number as Self)
+                    }
+                    _ => Err(ConversionError::new($expected, &value)),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_for_integer!(i32, "an integer in the range of i32");
+impl_try_from_for_integer!(u32, "an integer in the range of u32");
+impl_try_from_for_integer!(i64, "an integer");
+impl_try_from_for_integer!(usize, "an integer in the range of usize");
+
+impl TryFrom<Value> for String {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(string) => Ok(string),
+            _ => Err(ConversionError::new("a string", &value)),
+        }
+    }
+}
+
+// nil becomes None
+impl<T: TryFrom<Value, Error = ConversionError>> TryFrom<Value> for Option<T> {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Nil => Ok(None),
+            value => T::try_from(value).map(Some),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value>,
+    ConversionError: From<T::Error>,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::List(list) => list
+                .borrow()
+                .iter()
+                .map(|element| Ok(T::try_from(element.clone())?))
+                .collect(),
+            _ => Err(ConversionError::new("a list", &value)),
+        }
+    }
+}
+
+// Only maps with string keys can be converted
+impl<T, S> TryFrom<Value> for HashMap<String, T, S>
+where
+    T: TryFrom<Value>,
+    ConversionError: From<T::Error>,
+    S: BuildHasher + Default,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let Value::Map(map) = &value else {
+            return Err(ConversionError::new("a map", &value));
+        };
+
+        map.borrow()
+            .iter()
+            .map(|(key, value)| match key {
+                MapKey::String(key) => Ok((key.clone(), T::try_from(value.clone())?)),
+                key => Err(ConversionError::new("a string key", &key.to_value())),
+            })
+            .collect()
+    }
+}
+
+// A Rust function whose parameters and result are converted from and to Lox values, the type
+// parameter is the tuple of its parameter types
+pub trait TypedFunction<Args> {
+    fn arity(&self) -> usize;
+
+    /// Converts the arguments and calls the function.
+    ///
+    /// # Errors
+    ///
+    /// Fails if an argument can't be converted to the type of its parameter.
+    fn call(&self, arguments: &[Value]) -> Result<Value, ConversionError>;
+}
+
+macro_rules! impl_typed_function {
+    ($($arg:ident),*) => {
+        impl<F, R, $($arg),*> TypedFunction<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> R,
+            R: Into<Value>,
+            $($arg: TryFrom<Value>,)*
+            $(ConversionError: From<$arg::Error>,)*
+        {
+            fn arity(&self) -> usize {
+                let params: &[&str] = &[$(stringify!($arg)),*];
+                params.len()
+            }
+
+            #[allow(unused_variables, unused_mut)]
+            fn call(&self, arguments: &[Value]) -> Result<Value, ConversionError> {
+                let mut arguments = arguments.iter().cloned();
+
+                Ok(self($($arg::try_from(arguments.next().unwrap_or(Value::Nil))?),*).into())
+            }
+        }
+    };
+}
+
+impl_typed_function!();
+impl_typed_function!(A);
+impl_typed_function!(A, B);
+impl_typed_function!(A, B, C);
+impl_typed_function!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_to_values() {
+        assert_eq!(Value::Number(1.5), Value::from(1.5));
+        assert_eq!(Value::Number(3.0), Value::from(3));
+        assert_eq!(Value::String("a".to_string()), Value::from("a"));
+        assert_eq!(Value::Nil, Value::from(None::<bool>));
+        assert_eq!("[1, 2]", Value::from(vec![1, 2]).to_string());
+
+        let map = HashMap::from([("b".to_string(), true), ("a".to_string(), false)]);
+        assert_eq!("{a: false, b: true}", Value::from(map).to_string());
+    }
+
+    #[test]
+    fn convert_from_values() {
+        assert_eq!(Ok(2), i64::try_from(Value::Number(2.0)));
+        assert_eq!(Ok(Some(true)), Option::<bool>::try_from(Value::Bool(true)));
+        assert_eq!(Ok(None), Option::<bool>::try_from(Value::Nil));
+        assert_eq!(
+            Ok(vec!["a".to_string()]),
+            Vec::<String>::try_from(Value::from(vec!["a"]))
+        );
+
+        let error = Vec::<f64>::try_from(Value::from(vec!["a"])).unwrap_err();
+        assert_eq!("Expected a number but got string \"a\"", error.to_string());
+
+        let error = i64::try_from(Value::Number(1.5)).unwrap_err();
+        assert_eq!(
+            "Expected an integer but got number '1.5'",
+            error.to_string()
+        );
+
+        assert_eq!(Ok(-2), i32::try_from(Value::Number(-2.0)));
+        assert_eq!(Ok(u32::MAX), u32::try_from(Value::from(u32::MAX)));
+        assert_eq!(Ok(3), usize::try_from(Value::Number(3.0)));
+        assert!(i32::try_from(Value::Number(2_147_483_648.0)).is_err());
+        assert!(usize::try_from(Value::Number(18_446_744_073_709_551_616.0)).is_err());
+
+        let error = u32::try_from(Value::Number(-1.0)).unwrap_err();
+        assert_eq!(
+            "Expected an integer in the range of u32 but got number '-1'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn call_typed_function() {
+        let f = |a: f64, b: String| a > 1.0 && !b.is_empty();
+
+        assert_eq!(2, TypedFunction::arity(&f));
+        assert_eq!(
+            Ok(Value::Bool(true)),
+            TypedFunction::call(&f, &[Value::Number(2.0), Value::from("x")])
+        );
+        assert!(TypedFunction::call(&f, &[Value::Nil, Value::from("x")]).is_err());
+
+        let f = |n: i32, value: Value, values: Vec<Value>| format!("{n} {value} {}", values.len());
+        assert_eq!(
+            Ok(Value::from("-1 nil 2")),
+            TypedFunction::call(
+                &f,
+                &[Value::Number(-1.0), Value::Nil, Value::from(vec![1, 2])]
+            )
+        );
+    }
+}
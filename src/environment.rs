@@ -1,4 +1,5 @@
 use crate::error::RuntimeError;
+use crate::token::Token;
 use crate::value::Value;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -6,7 +7,7 @@ use std::rc::Rc;
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Environment {
-    parent: Option<Rc<RefCell<Environment>>>,
+    parent: Option<Rc<RefCell<Self>>>,
     values: HashMap<String, Value>,
 }
 
@@ -18,9 +19,9 @@ impl Environment {
         }
     }
 
-    pub fn new_with_parent(parent: Self) -> Self {
+    pub fn new_with_parent(parent: Rc<RefCell<Self>>) -> Self {
         Self {
-            parent: Some(Rc::new(RefCell::new(parent))),
+            parent: Some(parent),
             values: HashMap::new(),
         }
     }
@@ -35,13 +36,10 @@ impl Environment {
 
                 Some(old_value)
             }
-            None => match &self.parent {
-                Some(c) => {
-                    let mut env = c.borrow_mut();
-                    env.assign(name, value)
-                }
-                None => None,
-            },
+            None => self
+                .parent
+                .as_ref()
+                .and_then(|c| c.borrow_mut().assign(name, value)),
         }
     }
 
@@ -49,27 +47,16 @@ impl Environment {
         self.values.insert(name, value);
     }
 
-    pub fn get(&self, name: String) -> Result<Value, RuntimeError> {
-        match self.values.get(&name) {
-            Some(literal) => Ok(literal.clone()),
-            None => match &self.parent {
-                Some(c) => {
-                    let env = c.borrow();
-                    env.get(name)
-                }
-                None => Err(RuntimeError::UndefinedVariable(name)),
-            },
-        }
+    // Looks up a variable in this environment only, ignoring the parent environments
+    pub fn get_own(&self, name: &str) -> Option<Value> {
+        self.values.get(name).cloned()
     }
 
-    // Takes the parent, leaving None in its place.
-    pub fn take_parent(&mut self) -> Option<Self> {
-        if self.parent.is_none() {
-            None
-        } else {
-            let parent = Some(self.parent.as_ref().unwrap().take());
-            self.parent = None;
-            parent
+    pub fn get(&self, name: &Token) -> Result<Value, RuntimeError> {
+        match (self.values.get(&name.lexeme), &self.parent) {
+            (Some(literal), _) => Ok(literal.clone()),
+            (None, Some(c)) => c.borrow().get(name),
+            (None, None) => Err(RuntimeError::UndefinedVariable(name.clone())),
         }
     }
 }
@@ -77,6 +64,7 @@ impl Environment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::token_type::TokenType;
 
     #[test]
     fn assign_value_to_var() {
@@ -88,7 +76,7 @@ mod tests {
             .unwrap();
         assert_eq!(previous_value, Value::String("value".to_string()));
 
-        let result = env.get("key".to_string()).unwrap();
+        let result = env.get(&key()).unwrap();
         assert_eq!(Value::String("new value".to_string()), result);
     }
 
@@ -97,14 +85,14 @@ mod tests {
         let mut parent = Environment::new();
         parent.define("key".to_string(), Value::String("value".to_string()));
 
-        let mut env = Environment::new_with_parent(parent);
+        let mut env = Environment::new_with_parent(Rc::new(RefCell::new(parent)));
 
         let previous_value = env
             .assign("key".to_string(), Value::String("new value".to_string()))
             .unwrap();
         assert_eq!(previous_value, Value::String("value".to_string()));
 
-        let result = env.get("key".to_string()).unwrap();
+        let result = env.get(&key()).unwrap();
         assert_eq!(Value::String("new value".to_string()), result);
     }
 
@@ -121,7 +109,7 @@ mod tests {
         let mut env = Environment::new();
         env.define("key".to_string(), Value::String("value".to_string()));
 
-        let result = env.get("key".to_string()).unwrap();
+        let result = env.get(&key()).unwrap();
         assert_eq!(Value::String("value".to_string()), result);
     }
 
@@ -130,37 +118,38 @@ mod tests {
         let mut parent = Environment::new();
         parent.define("key".to_string(), Value::String("value".to_string()));
 
-        let env = Environment::new_with_parent(parent);
+        let env = Environment::new_with_parent(Rc::new(RefCell::new(parent)));
 
-        let result = env.get("key".to_string()).unwrap();
+        let result = env.get(&key()).unwrap();
         assert_eq!(Value::String("value".to_string()), result);
     }
 
     #[test]
     fn get_value_of_undefined_var() {
         let env = Environment::new();
-        let error = env.get("key".to_string()).unwrap_err();
+        let error = env.get(&key()).unwrap_err();
 
         match error {
-            RuntimeError::UndefinedVariable(var) => assert_eq!("key".to_string(), var),
-            _ => assert!(false),
+            RuntimeError::UndefinedVariable(token) => assert_eq!(key(), token),
+            _ => panic!("unexpected error"),
         }
     }
 
     #[test]
-    fn take_parent() {
-        let parent = Environment::new();
-        let mut env = Environment::new_with_parent(parent.clone());
+    fn assign_value_to_var_from_shared_parent_environment() {
+        let parent = Rc::new(RefCell::new(Environment::new()));
+        parent
+            .borrow_mut()
+            .define("key".to_string(), Value::String("value".to_string()));
 
-        let result = env.take_parent().unwrap();
-        assert_eq!(parent, result);
-        assert!(env.take_parent().is_none());
-    }
+        let mut env = Environment::new_with_parent(Rc::clone(&parent));
+        env.assign("key".to_string(), Value::String("new value".to_string()));
 
-    #[test]
-    fn take_parent_if_there_is_none() {
-        let result = Environment::new().take_parent();
+        let result = parent.borrow().get(&key()).unwrap();
+        assert_eq!(Value::String("new value".to_string()), result);
+    }
 
-        assert!(result.is_none());
+    fn key() -> Token {
+        Token::new(TokenType::Identifier("key".to_string()), 1)
     }
 }
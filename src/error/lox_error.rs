@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::error::{ParseError, RuntimeError, ScanError};
+
+// The errors that can occur when running a program with `Lox`
+#[derive(Debug)]
+pub enum LoxError {
+    Io(io::Error),
+    Parse(Vec<ParseError>),
+    Runtime(RuntimeError),
+    Scan(ScanError),
+}
+
+impl Error for LoxError {}
+
+impl fmt::Display for LoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{error}"),
+            Self::Parse(errors) => {
+                let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                write!(f, "{}", messages.join("\n"))
+            }
+            Self::Runtime(error) => write!(f, "{error}"),
+            Self::Scan(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl From<io::Error> for LoxError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<Vec<ParseError>> for LoxError {
+    fn from(errors: Vec<ParseError>) -> Self {
+        Self::Parse(errors)
+    }
+}
+
+impl From<RuntimeError> for LoxError {
+    fn from(error: RuntimeError) -> Self {
+        Self::Runtime(error)
+    }
+}
+
+impl From<ScanError> for LoxError {
+    fn from(error: ScanError) -> Self {
+        Self::Scan(error)
+    }
+}
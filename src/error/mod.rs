@@ -1,7 +1,9 @@
+pub use self::lox_error::LoxError;
 pub use self::parse_error::ParseError;
 pub use self::runtime_error::RuntimeError;
 pub use self::scan_error::ScanError;
 
+mod lox_error;
 mod parse_error;
 mod runtime_error;
 mod scan_error;
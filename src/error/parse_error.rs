@@ -4,17 +4,31 @@ use std::fmt;
 
 #[derive(Debug, PartialEq)]
 pub enum ParseError {
+    ExportNotAtTopLevel(Token),
     InvalidToken(Token),
+    KeywordOutsideLoop(Token),
     MissingBraceAfterBlock(Token),
     MissingBraceAfterClassBody(Token),
+    MissingBraceAfterEntries(Token),
     MissingBraceBeforeBody(Token, String),
     MissingBraceBeforeClassBody(Token),
+    MissingBracketAfterElements(Token),
+    MissingBracketAfterIndex(Token),
+    MissingCatchOrFinally(Token),
     MissingClassName(Token),
+    MissingModulePath(Token),
+    MissingColonAfterKey(Token),
+    MissingDeclarationAfterExport(Token),
+    MissingExpression(Token),
+    MissingImportAfterModulePath(Token),
     MissingName(Token, String),
     MissingParameterName(Token),
     MissingParenAfterArguments(Token),
+    MissingParenAfterCatch(Token),
+    MissingParenAfterCatchVariable(Token),
     MissingParenAfterExpression(Token),
     MissingParenAfterFor(Token),
+    MissingParenAfterFun(Token),
     MissingParenAfterForClauses(Token),
     MissingParenAfterIf(Token),
     MissingParenAfterIfCondition(Token),
@@ -23,12 +37,15 @@ pub enum ParseError {
     MissingParenAfterWhile(Token),
     MissingParenAfterWhileCondition(Token),
     MissingPropertyName(Token),
+    MissingSemicolonAfterImport(Token),
+    MissingSemicolonAfterKeyword(Token),
     MissingSemicolonAfterLoopCondition(Token),
     MissingSemicolonAfterReturnValue(Token),
     MissingSemicolonAfterValue(Token),
     MissingSemicolonAfterVariableDeclaration(Token),
     MissingVariableName(Token),
     UnexpectedError,
+    UnexpectedTokenAfterExpression(Token),
 }
 
 impl Error for ParseError {}
@@ -37,6 +54,11 @@ impl fmt::Display for ParseError {
     #[allow(clippy::too_many_lines)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::ExportNotAtTopLevel(token) => write!(
+                f,
+                "Only top-level declarations can be exported, on line {}.",
+                token.line
+            ),
             Self::InvalidToken(token) => {
                 write!(
                     f,
@@ -44,12 +66,22 @@ impl fmt::Display for ParseError {
                     token.lexeme, token.line
                 )
             }
+            Self::KeywordOutsideLoop(token) => {
+                write!(
+                    f,
+                    "Can't use '{}' outside of a loop on line {}.",
+                    token.lexeme, token.line
+                )
+            }
             Self::MissingBraceAfterBlock(token) => {
                 write!(f, "Expect '}}' after block on line {}.", token.line)
             }
             Self::MissingBraceAfterClassBody(token) => {
                 write!(f, "Expect '}}' after class body on line {}.", token.line)
             }
+            Self::MissingBraceAfterEntries(token) => {
+                write!(f, "Expect '}}' after map entries on line {}.", token.line)
+            }
             Self::MissingBraceBeforeBody(token, kind) => {
                 write!(
                     f,
@@ -60,9 +92,45 @@ impl fmt::Display for ParseError {
             Self::MissingBraceBeforeClassBody(token) => {
                 write!(f, "Expect '{{' before class body on line {}.", token.line)
             }
+            Self::MissingBracketAfterElements(token) => {
+                write!(f, "Expect ']' after list elements on line {}.", token.line)
+            }
+            Self::MissingBracketAfterIndex(token) => {
+                write!(f, "Expect ']' after index on line {}.", token.line)
+            }
+            Self::MissingCatchOrFinally(token) => write!(
+                f,
+                "Expect 'catch' or 'finally' after 'try' block on line {}.",
+                token.line
+            ),
             Self::MissingClassName(token) => {
                 write!(f, "Expect class name after 'class' on line {}.", token.line)
             }
+            Self::MissingColonAfterKey(token) => {
+                write!(f, "Expect ':' after map key on line {}.", token.line)
+            }
+            Self::MissingDeclarationAfterExport(token) => write!(
+                f,
+                "Expect variable, function or class declaration after 'export' on line {}.",
+                token.line
+            ),
+            Self::MissingExpression(token) => {
+                write!(f, "Expect expression on line {}.", token.line)
+            }
+            Self::MissingImportAfterModulePath(token) => {
+                write!(
+                    f,
+                    "Expect 'import' after module path on line {}.",
+                    token.line
+                )
+            }
+            Self::MissingModulePath(token) => {
+                write!(
+                    f,
+                    "Expect module path after 'import' on line {}.",
+                    token.line
+                )
+            }
             Self::MissingName(token, kind) => {
                 write!(f, "Expect {} name on line {}.", kind, token.line)
             }
@@ -72,6 +140,14 @@ impl fmt::Display for ParseError {
             Self::MissingParenAfterArguments(token) => {
                 write!(f, "Expect ')' after arguments on line {}.", token.line)
             }
+            Self::MissingParenAfterCatch(token) => {
+                write!(f, "Expect '(' after 'catch' on line {}.", token.line)
+            }
+            Self::MissingParenAfterCatchVariable(token) => write!(
+                f,
+                "Expect ')' after 'catch' variable on line {}.",
+                token.line
+            ),
             Self::MissingParenAfterExpression(token) => {
                 write!(f, "Expect ')' after expression on line {}.", token.line)
             }
@@ -81,6 +157,9 @@ impl fmt::Display for ParseError {
             Self::MissingParenAfterForClauses(token) => {
                 write!(f, "Expect ')' after 'for' clauses on line {}.", token.line)
             }
+            Self::MissingParenAfterFun(token) => {
+                write!(f, "Expect '(' after 'fun' on line {}.", token.line)
+            }
             Self::MissingParenAfterIf(token) => {
                 write!(f, "Expect '(' after 'if' on line {}.", token.line)
             }
@@ -110,6 +189,16 @@ impl fmt::Display for ParseError {
             Self::MissingPropertyName(token) => {
                 write!(f, "Expect property name after '.' on line {}.", token.line)
             }
+            Self::MissingSemicolonAfterImport(token) => {
+                write!(f, "Expect ';' after import on line {}.", token.line)
+            }
+            Self::MissingSemicolonAfterKeyword(token) => {
+                write!(
+                    f,
+                    "Expect ';' after '{}' on line {}.",
+                    token.lexeme, token.line
+                )
+            }
             Self::MissingSemicolonAfterLoopCondition(token) => {
                 write!(f, "Expect ';' after loop condition on line {}.", token.line)
             }
@@ -136,6 +225,11 @@ impl fmt::Display for ParseError {
             Self::UnexpectedError => {
                 write!(f, "Unexpected error.")
             }
+            Self::UnexpectedTokenAfterExpression(token) => write!(
+                f,
+                "Expect end of expression but got '{}' on line {}.",
+                token.lexeme, token.line
+            ),
         }
     }
 }
@@ -1,35 +1,173 @@
+use crate::capability::Capability;
 use crate::token::Token;
 use crate::value::Value;
 use std::error::Error;
 use std::fmt;
+use std::time::Duration;
 
 type Line = usize;
 
 #[derive(Debug)]
 pub enum RuntimeError {
+    ArityMismatch(usize, usize, Line),
+    Break,
+    CallDepthExceeded(usize, Line),
+    Cancelled(Line),
+    CapabilityDenied(String, Capability, Line),
+    Continue,
+    CyclicImport(String, Line),
+    EmptyList(Line),
+    HostError(String, String, Line),
+    ImportFailed(String, String, Line),
+    IndexOutOfBounds(f64, usize, Line),
+    InvalidArgument(String, Line),
+    InvalidIndex(Value, Line),
+    InvalidKey(Value, Line),
     InvalidOperator(Token),
+    Io(String, Line),
+    NotExported(String, Token),
     NumberExpectedAfterMinus(Line),
+    OnlyInstancesHaveFields(Token),
+    OutOfMemory(usize, Line),
     Return(Value),
-    UndefinedVariable(String),
-    ValueNotCallable(Value),
+    StackLimitExceeded(usize, Line),
+    StepLimitExceeded(u64, Line),
+    Throw(Value, Line),
+    Timeout(Duration, Line),
+    UndefinedProperty(Token),
+    UndefinedVariable(Token),
+    ValueNotCallable(Value, Line),
+    ValueNotIndexable(Value, Line),
+    ValueNotIterable(Value, Line),
+}
+
+impl RuntimeError {
+    // Returns the line on which the error occurred, or None for control flow
+    #[must_use]
+    pub const fn line(&self) -> Option<Line> {
+        match self {
+            Self::Break | Self::Continue | Self::Return(_) => None,
+            Self::ArityMismatch(_, _, line)
+            | Self::CallDepthExceeded(_, line)
+            | Self::Cancelled(line)
+            | Self::CapabilityDenied(_, _, line)
+            | Self::CyclicImport(_, line)
+            | Self::EmptyList(line)
+            | Self::HostError(_, _, line)
+            | Self::ImportFailed(_, _, line)
+            | Self::IndexOutOfBounds(_, _, line)
+            | Self::InvalidArgument(_, line)
+            | Self::InvalidIndex(_, line)
+            | Self::InvalidKey(_, line)
+            | Self::Io(_, line)
+            | Self::NumberExpectedAfterMinus(line)
+            | Self::OutOfMemory(_, line)
+            | Self::StackLimitExceeded(_, line)
+            | Self::StepLimitExceeded(_, line)
+            | Self::Throw(_, line)
+            | Self::Timeout(_, line)
+            | Self::ValueNotCallable(_, line)
+            | Self::ValueNotIndexable(_, line)
+            | Self::ValueNotIterable(_, line) => Some(*line),
+            Self::InvalidOperator(token)
+            | Self::NotExported(_, token)
+            | Self::OnlyInstancesHaveFields(token)
+            | Self::UndefinedProperty(token)
+            | Self::UndefinedVariable(token) => Some(token.line),
+        }
+    }
+
+    // Exceptions can be caught by 'try' statements, 'break', 'continue' and 'return' can't, nor
+    // can exceeded limits
+    #[must_use]
+    pub const fn is_exception(&self) -> bool {
+        self.line().is_some() && !self.is_limit()
+    }
+
+    // Returns true if the program was cancelled or used up one of its limits
+    #[must_use]
+    pub const fn is_limit(&self) -> bool {
+        matches!(
+            self,
+            Self::CallDepthExceeded(..)
+                | Self::Cancelled(_)
+                | Self::OutOfMemory(..)
+                | Self::StackLimitExceeded(..)
+                | Self::StepLimitExceeded(..)
+                | Self::Timeout(..)
+        )
+    }
+
+    // Returns the description of the error without its location, e.g. for the 'message' of
+    // caught errors
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn message(&self) -> String {
+        match self {
+            Self::ArityMismatch(expected, got, _) => {
+                format!("Expected {expected} arguments but got {got}")
+            }
+            Self::Break => "break".to_string(),
+            Self::CallDepthExceeded(max, _) => format!("Maximum call depth of {max} exceeded"),
+            Self::Cancelled(_) => "Execution cancelled".to_string(),
+            Self::CapabilityDenied(name, capability, _) => {
+                format!("'{name}' requires the '{capability}' capability, which isn't allowed")
+            }
+            Self::Continue => "continue".to_string(),
+            Self::CyclicImport(path, _) => format!("Cyclic import of '{path}'"),
+            Self::EmptyList(_) => "List is empty".to_string(),
+            Self::HostError(name, message, _) => format!("{name}: {message}"),
+            Self::ImportFailed(path, reason, _) => format!("Can't import '{path}': {reason}"),
+            Self::IndexOutOfBounds(index, len, _) => {
+                format!("Index {index} out of bounds for length {len}")
+            }
+            Self::InvalidArgument(message, _) => message.clone(),
+            Self::InvalidIndex(index, _) => {
+                format!("Index must be a non-negative integer, got '{index}'")
+            }
+            Self::InvalidKey(key, _) => {
+                format!("Only strings, numbers, booleans and nil can be used as keys, got '{key}'")
+            }
+            Self::InvalidOperator(token) => format!("Invalid operator '{}'", token.lexeme),
+            Self::Io(message, _) => format!("I/O error: {message}"),
+            Self::NotExported(module, token) => {
+                format!("Module '{module}' doesn't export '{}'", token.lexeme)
+            }
+            Self::NumberExpectedAfterMinus(_) => "Number expected after '-'".to_string(),
+            Self::OnlyInstancesHaveFields(token) => {
+                format!("Only instances have fields, can't set '{}'", token.lexeme)
+            }
+            Self::OutOfMemory(max, _) => format!("Memory limit of {max} bytes exceeded"),
+            Self::Return(value) => value.to_string(),
+            Self::StackLimitExceeded(max, _) => format!("Stack limit of {max} bytes exceeded"),
+            Self::StepLimitExceeded(max, _) => {
+                format!("Limit of {max} executed statements exceeded")
+            }
+            Self::Throw(Value::Instance(instance), _) => {
+                let instance = instance.borrow();
+                instance.get_field("message").map_or_else(
+                    || format!("Uncaught exception '{instance}'"),
+                    |message| format!("{}: {message}", instance.class().name()),
+                )
+            }
+            Self::Throw(value, _) => format!("Uncaught exception '{value}'"),
+            Self::Timeout(timeout, _) => format!("Time limit of {timeout:?} exceeded"),
+            Self::UndefinedProperty(token) => format!("Undefined property '{}'", token.lexeme),
+            Self::UndefinedVariable(token) => format!("Undefined variable '{}'", token.lexeme),
+            Self::ValueNotCallable(value, _) => format!("Value not callable: '{value}'"),
+            Self::ValueNotIndexable(value, _) => format!("Value not indexable: '{value}'"),
+            Self::ValueNotIterable(value, _) => format!("Value not iterable: '{value}'"),
+        }
+    }
 }
 
 impl Error for RuntimeError {}
 
 impl fmt::Display for RuntimeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::InvalidOperator(token) => write!(
-                f,
-                "Invalid operator '{}' on line {}",
-                token.lexeme, token.line
-            ),
-            Self::NumberExpectedAfterMinus(line) => {
-                write!(f, "Number expected after '-' on line {}", line)
-            }
-            Self::Return(value) => write!(f, "{}", value),
-            Self::UndefinedVariable(var) => write!(f, "Undefined variable: '{}'", var),
-            Self::ValueNotCallable(value) => write!(f, "Value not callable: '{}'", value),
+        match self.line() {
+            Some(line) => write!(f, "{} on line {line}", self.message()),
+            None => write!(f, "{}", self.message()),
         }
     }
 }
@@ -16,13 +16,13 @@ impl fmt::Display for ScanError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::NumberEndsWithDot(line) => {
-                write!(f, "Number ends with '.' on line {}", line)
+                write!(f, "Number ends with '.' on line {line}")
             }
             Self::UnexpectedChar(c, line) => {
-                write!(f, "Unexpected character '{}' on line {}", c, line)
+                write!(f, "Unexpected character '{c}' on line {line}")
             }
             Self::UnterminatedString(line) => {
-                write!(f, "Unterminated string starting on line {}", line)
+                write!(f, "Unterminated string starting on line {line}")
             }
         }
     }
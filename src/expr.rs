@@ -1,44 +1,68 @@
 use crate::literal::Literal;
+use crate::stmt::Stmt;
 use crate::token::Token;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expr {
     Assign {
         name: Token,
-        value: Box<Expr>,
+        value: Box<Self>,
     },
     Binary {
-        left: Box<Expr>,
+        left: Box<Self>,
         operator: Token,
-        right: Box<Expr>,
+        right: Box<Self>,
     },
     Call {
-        callee: Box<Expr>,
+        callee: Box<Self>,
         paren: Token,
-        arguments: Vec<Expr>,
+        arguments: Vec<Self>,
     },
     Get {
-        object: Box<Expr>,
+        object: Box<Self>,
         name: Token,
     },
+    GetIndex {
+        object: Box<Self>,
+        bracket: Token,
+        index: Box<Self>,
+    },
     Grouping {
-        expression: Box<Expr>,
+        expression: Box<Self>,
+    },
+    Lambda {
+        keyword: Token,
+        params: Vec<Token>,
+        body: Vec<Stmt>,
+    },
+    List {
+        elements: Vec<Self>,
     },
     Literal(Literal),
+    Map {
+        brace: Token,
+        entries: Vec<(Self, Self)>,
+    },
     Logical {
-        left: Box<Expr>,
+        left: Box<Self>,
         operator: Token,
-        right: Box<Expr>,
+        right: Box<Self>,
     },
     Set {
-        object: Box<Expr>,
+        object: Box<Self>,
         name: Token,
-        value: Box<Expr>,
+        value: Box<Self>,
+    },
+    SetIndex {
+        object: Box<Self>,
+        bracket: Token,
+        index: Box<Self>,
+        value: Box<Self>,
     },
     This(Token),
     Unary {
         operator: Token,
-        right: Box<Expr>,
+        right: Box<Self>,
     },
     Variable(Token),
 }
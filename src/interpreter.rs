@@ -1,63 +1,536 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::capability::Capability;
 use crate::clock::Clock;
+use crate::conversion::TypedFunction;
 use crate::environment::Environment;
-use crate::error::RuntimeError;
+use crate::error::{LoxError, RuntimeError};
 use crate::expr::Expr;
+use crate::limits::{Allocation, CancelHandle, Limits, MemoryUsage};
 use crate::literal::Literal;
+use crate::lox_callable::LoxCallable;
+use crate::lox_class::LoxClass;
 use crate::lox_function::LoxFunction;
+use crate::lox_instance::LoxInstance;
+use crate::lox_iterator::LoxIterator;
+use crate::lox_map::LoxMap;
+use crate::lox_module::{self, LoxModule};
+use crate::native::host::HostFunction;
+use crate::native::{fs, list, map, math, random, string, system, types};
+use crate::parser::Parser;
+use crate::random::Rng;
+use crate::scanner::Scanner;
 use crate::stmt::Stmt;
+use crate::time_source::{FixedTimeSource, SystemTimeSource, TimeSource};
 use crate::token::Token;
 use crate::token_type::TokenType;
 use crate::value::Value;
 
 type InterpretResult<T> = Result<T, RuntimeError>;
 
+// Classes that are defined before any program runs
+const PRELUDE: &str = r"
+    class Error {
+        init(message) {
+            this.message = message;
+            this.line = nil;
+        }
+    }";
+
 pub struct Interpreter {
-    pub environment: Environment,
+    // the innermost scope of the code that is executed
+    environment: Rc<RefCell<Environment>>,
+    // the built-in functions and classes, the parent of the environments of the script and modules
+    globals: Rc<RefCell<Environment>>,
+    // the file that is executed, imports are resolved relative to it
+    script_path: Option<PathBuf>,
+    // every module is only executed once, later imports get the same module
+    modules: HashMap<PathBuf, Rc<LoxModule>>,
+    // the modules that are currently being executed, used to detect cyclic imports
+    imports: Vec<PathBuf>,
+    // the groups of natives the script may use, see Capability
+    capabilities: HashSet<Capability>,
+    // where clock gets the time from
+    time_source: Box<dyn TimeSource>,
+    // the generator of random and random_int
+    rng: Rng,
+    // the command-line arguments after the script
+    script_args: Vec<String>,
+    // where print writes to, stdout unless the program is embedded
+    output: Box<dyn Write>,
+    limits: Limits,
+    // the number of statements executed in the current run
+    steps: u64,
+    // the time the current run has to end by, if there is a timeout
+    deadline: Option<Instant>,
+    // the memory allocated in the current run
+    memory_usage: MemoryUsage,
+    // the address of the native stack where the current run started
+    stack_base: usize,
+    cancel_handle: CancelHandle,
+    // the class of the values that built-in errors are converted to when they are caught
+    error_class: Rc<LoxClass>,
+    // the functions that are currently being called, with the lines they were called on
+    call_stack: Vec<(String, usize)>,
+    // the call stack at the point the current exception was raised, innermost call first
+    stack_trace: Vec<(String, usize)>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
+    // the prelude is known to be valid, so creating an interpreter never panics
+    #[allow(clippy::missing_panics_doc)]
+    #[must_use]
     pub fn new() -> Self {
         let mut env = Environment::new();
         env.define("clock".to_string(), Value::Function(Box::new(Clock::new())));
 
-        Self { environment: env }
+        let natives = list::globals()
+            .into_iter()
+            .chain(math::globals())
+            .chain(types::globals())
+            .chain(fs::globals())
+            .chain(random::globals())
+            .chain(system::globals());
+        for native in natives {
+            env.define(native.name().to_string(), Value::Function(Box::new(native)));
+        }
+
+        for (name, value) in math::constants() {
+            env.define(name.to_string(), value);
+        }
+
+        let globals = Rc::new(RefCell::new(env));
+        let mut interpreter = Self {
+            environment: Rc::clone(&globals),
+            globals,
+            script_path: None,
+            modules: HashMap::new(),
+            imports: Vec::new(),
+            capabilities: Capability::ALL.into_iter().collect(),
+            time_source: Box::new(SystemTimeSource),
+            rng: Rng::from_time(),
+            script_args: Vec::new(),
+            output: Box::new(io::stdout()),
+            limits: Limits::default(),
+            steps: 0,
+            deadline: None,
+            memory_usage: MemoryUsage::default(),
+            cancel_handle: CancelHandle::new(),
+            // replaced by the class from the prelude below
+            error_class: Rc::new(LoxClass::new("Error", HashMap::new())),
+            call_stack: Vec::new(),
+            stack_base: 0,
+            stack_trace: Vec::new(),
+        };
+
+        let prelude = Parser::new()
+            .parse(Scanner::scan(PRELUDE).expect("the prelude can be scanned"))
+            .expect("the prelude can be parsed");
+        interpreter
+            .interpret(&prelude)
+            .expect("the prelude can be interpreted");
+
+        let error_class = interpreter
+            .environment
+            .borrow()
+            .get(&Token::new(TokenType::Identifier("Error".to_string()), 0));
+        if let Ok(Value::Class(class)) = error_class {
+            interpreter.error_class = class;
+        }
+
+        // the script gets its own environment, just like modules
+        interpreter.environment = Rc::new(RefCell::new(Environment::new_with_parent(Rc::clone(
+            &interpreter.globals,
+        ))));
+
+        interpreter
+    }
+
+    /// Makes a Rust closure available as global function. Errors returned by the closure are
+    /// raised as `RuntimeError::HostError`.
+    pub fn register_fn<F, E>(&mut self, name: &str, arity: usize, function: F)
+    where
+        F: Fn(&[Value]) -> Result<Value, E> + 'static,
+        E: fmt::Display,
+    {
+        let function = HostFunction::new(name, arity, function);
+        self.define_global(name, Value::Function(Box::new(function)));
+    }
+
+    /// Makes a Rust function available as global function, e.g. a `fn(f64, String) -> bool`.
+    /// The arguments are converted to the parameter types, and the result to a Lox value.
+    /// Failed conversions are raised as `RuntimeError::HostError`.
+    pub fn register_typed_fn<Args, F>(&mut self, name: &str, function: F)
+    where
+        F: TypedFunction<Args> + 'static,
+    {
+        let arity = function.arity();
+        self.register_fn(name, arity, move |arguments| function.call(arguments));
+    }
+
+    // Globals are visible to the script and all modules
+    pub fn define_global(&mut self, name: &str, value: Value) {
+        self.globals.borrow_mut().define(name.to_string(), value);
+    }
+
+    // Creates an interpreter whose scripts may only use the natives of the given capabilities
+    #[must_use]
+    pub fn with_capabilities(capabilities: &[Capability]) -> Self {
+        let mut interpreter = Self::new();
+        interpreter.set_capabilities(capabilities);
+
+        interpreter
+    }
+
+    #[must_use]
+    pub fn is_allowed(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    pub fn set_capabilities(&mut self, capabilities: &[Capability]) {
+        self.capabilities = capabilities.iter().copied().collect();
+    }
+
+    pub fn allow(&mut self, capability: Capability) {
+        self.capabilities.insert(capability);
+    }
+
+    pub fn deny(&mut self, capability: Capability) {
+        self.capabilities.remove(&capability);
+    }
+
+    pub(crate) fn time_source(&self) -> &dyn TimeSource {
+        self.time_source.as_ref()
+    }
+
+    pub fn set_time_source(&mut self, time_source: impl TimeSource + 'static) {
+        self.time_source = Box::new(time_source);
+    }
+
+    pub(crate) const fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    // The random numbers of a seed are always the same
+    pub const fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    // Makes runs reproducible: the clock is frozen at 0 and random numbers come from the seed
+    pub fn set_deterministic(&mut self, seed: u64) {
+        self.set_time_source(FixedTimeSource(0.0));
+        self.set_seed(seed);
+    }
+
+    // Fails if the script may not call the native with the given name
+    pub(crate) fn require(
+        &self,
+        capability: Capability,
+        name: &str,
+        line: usize,
+    ) -> InterpretResult<()> {
+        if self.is_allowed(capability) {
+            Ok(())
+        } else {
+            Err(RuntimeError::CapabilityDenied(
+                name.to_string(),
+                capability,
+                line,
+            ))
+        }
+    }
+
+    // Redirects the output of print, e.g. to capture it in tests or to stream it to a client
+    pub fn set_output(&mut self, output: impl Write + 'static) {
+        self.output = Box::new(output);
+    }
+
+    // Gives natives that print, like input, access to the output
+    pub(crate) fn output(&mut self) -> &mut dyn Write {
+        &mut self.output
+    }
+
+    #[must_use]
+    pub const fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    pub const fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    // A handle that other threads can use to stop the running program
+    #[must_use]
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    #[must_use]
+    pub const fn memory_usage(&self) -> MemoryUsage {
+        self.memory_usage
+    }
+
+    #[must_use]
+    pub fn script_args(&self) -> &[String] {
+        &self.script_args
+    }
+
+    pub fn set_script_args(&mut self, args: &[String]) {
+        self.script_args = args.to_vec();
+    }
+
+    pub fn set_script_path(&mut self, path: &Path) {
+        self.script_path = Some(path.to_path_buf());
+
+        // modules importing the script itself are cyclic imports as well
+        if let Ok(path) = path.canonicalize() {
+            self.imports = vec![path];
+        }
+    }
+
+    // The calls that were active when the last uncaught exception was raised, innermost call
+    // first, together with the lines they were called on
+    #[must_use]
+    pub fn stack_trace(&self) -> &[(String, usize)] {
+        &self.stack_trace
+    }
+
+    /// Executes the statements and returns the value of the last statement if that is an
+    /// expression statement, and nil otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first uncaught error raised by the statements.
+    pub fn interpret(&mut self, statements: &[Stmt]) -> InterpretResult<Value> {
+        self.run(|interpreter| {
+            let base = interpreter.import_base().to_path_buf();
+            interpreter.check_imports(statements, &base, &mut HashSet::new())?;
+            interpreter.interpret_statements(statements)
+        })
+    }
+
+    /// Evaluates a single expression, e.g. a rule like `price * qty > 100` written by a user.
+    /// The bindings are variables that are only visible to the expression, they shadow globals
+    /// and variables of earlier runs.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the source isn't a single expression, statements aren't allowed, or with the
+    /// error raised by evaluating it.
+    pub fn eval_expr(
+        &mut self,
+        source: &str,
+        bindings: &[(&str, Value)],
+    ) -> Result<Value, LoxError> {
+        let tokens = Scanner::scan(source)?;
+        let expr = Parser::new().parse_expression(tokens)?;
+
+        let mut env = Environment::new_with_parent(Rc::clone(&self.environment));
+        for (name, value) in bindings {
+            env.define((*name).to_string(), value.clone());
+        }
+
+        let previous = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(env)));
+        let result = self.run(|interpreter| interpreter.evaluate(&expr));
+        self.environment = previous;
+
+        Ok(result?)
+    }
+
+    /// Calls a function or class, e.g. a handler defined by a script that was run before. Errors
+    /// of the call itself are reported on line 0, as it isn't part of a script.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value isn't callable, if the number of arguments doesn't match its arity, or
+    /// with the uncaught error raised by the function.
+    pub fn call(&mut self, callee: &Value, args: &[Value]) -> InterpretResult<Value> {
+        self.run(|interpreter| interpreter.call_value(callee.clone(), args.to_vec(), 0))
+    }
+
+    // Returns the value of a global variable defined by a script, or of a built-in
+    #[must_use]
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        let name = Token::new(TokenType::Identifier(name.to_string()), 0);
+
+        self.environment.borrow().get(&name).ok()
+    }
+
+    // Runs a program or an expression, the limits apply to each run separately
+    fn run<T>(&mut self, run: impl FnOnce(&mut Self) -> InterpretResult<T>) -> InterpretResult<T> {
+        // the stack trace of an earlier run is outdated
+        self.stack_trace.clear();
+        self.steps = 0;
+        self.deadline = self.limits.timeout.map(|timeout| Instant::now() + timeout);
+        self.memory_usage = MemoryUsage::default();
+        // a host function called by the script may start a nested run, which uses the same stack
+        if self.call_stack.is_empty() {
+            self.stack_base = stack_address();
+        }
+
+        run(self)
+    }
+
+    fn interpret_statements(&mut self, statements: &[Stmt]) -> InterpretResult<Value> {
+        let Some((last, rest)) = statements.split_last() else {
+            return Ok(Value::Nil);
+        };
+
+        for statement in rest {
+            self.execute(statement)?;
+        }
+
+        if let Stmt::Expr(expr) = last {
+            self.evaluate(expr)
+        } else {
+            self.execute(last)?;
+            Ok(Value::Nil)
+        }
+    }
+
+    // Fails if the program used up one of its limits or was cancelled. Checked on every loop
+    // iteration and call, as only loops and calls can keep a program running.
+    fn check_limits(&self, line: usize) -> InterpretResult<()> {
+        if self.cancel_handle.is_cancelled() {
+            // a cancellation only stops a single run, as the error can't be caught
+            self.cancel_handle.reset();
+            return Err(RuntimeError::Cancelled(line));
+        }
+
+        if let Some(max) = self.limits.max_steps {
+            if self.steps > max {
+                return Err(RuntimeError::StepLimitExceeded(max, line));
+            }
+        }
+
+        if let (Some(timeout), Some(deadline)) = (self.limits.timeout, self.deadline) {
+            if Instant::now() >= deadline {
+                return Err(RuntimeError::Timeout(timeout, line));
+            }
+        }
+
+        // some allocations are recorded without a check, as they have no line to report
+        if let Some(max) = self.limits.max_memory {
+            if self.memory_usage.total() > max {
+                return Err(RuntimeError::OutOfMemory(max, line));
+            }
+        }
+
+        Ok(())
     }
 
-    pub fn interpret(&mut self, statements: Vec<Stmt>) -> InterpretResult<()> {
-        for statement in statements {
-            self.execute(&statement)?;
+    // Records an allocation, fails without recording it if it would exceed the memory limit
+    pub(crate) const fn allocate(
+        &mut self,
+        allocation: Allocation,
+        line: usize,
+    ) -> InterpretResult<()> {
+        let mut usage = self.memory_usage;
+        usage.add(allocation);
+
+        if let Some(max) = self.limits.max_memory {
+            if usage.total() > max {
+                return Err(RuntimeError::OutOfMemory(max, line));
+            }
         }
 
+        self.memory_usage = usage;
         Ok(())
     }
 
+    pub(crate) const fn release(&mut self, allocation: Allocation) {
+        self.memory_usage.release(allocation);
+    }
+
     fn execute(&mut self, statement: &Stmt) -> InterpretResult<()> {
+        self.steps += 1;
+
         match statement {
             Stmt::Block(statements) => {
-                self.execute_block(statements, &self.environment.clone())?;
+                let env = Environment::new_with_parent(Rc::clone(&self.environment));
+                self.execute_block(statements, env)?;
+            }
+            Stmt::Break(_) => return Err(RuntimeError::Break),
+            Stmt::Class(name, methods) => {
+                let mut class_methods = HashMap::new();
+
+                for method in methods {
+                    if let Stmt::Function(method_name, params, body) = method {
+                        let f = LoxFunction::new_method(
+                            method_name,
+                            params,
+                            body,
+                            Rc::clone(&self.environment),
+                        );
+                        class_methods.insert(method_name.lexeme.clone(), f);
+                    }
+                }
+
+                let class = LoxClass::new(&name.lexeme, class_methods);
+                self.environment
+                    .borrow_mut()
+                    .define(name.lexeme.clone(), Value::Class(Rc::new(class)));
             }
-            Stmt::Class(name, methods) => {} // TODO implement
+            Stmt::Continue(_) => return Err(RuntimeError::Continue),
+            Stmt::Export(_, declaration) => self.execute(declaration)?,
             Stmt::Expr(expr) => {
-                self.evaluate(&*expr)?;
+                self.evaluate(expr)?;
             }
+            Stmt::ForIn(name, iterable, body) => self.execute_for_in(name, iterable, body)?,
             Stmt::If(condition, then_branch, else_branch) => {
-                if let Ok(literal) = self.evaluate(&*condition) {
-                    if literal.is_truthy() {
-                        self.execute(&*then_branch)?;
-                    } else if *else_branch != None {
-                        self.execute(&*else_branch.as_ref().unwrap())?;
-                    }
+                if self.evaluate(condition)?.is_truthy() {
+                    self.execute(then_branch)?;
+                } else if let Some(else_branch) = else_branch {
+                    self.execute(else_branch)?;
                 }
             }
             Stmt::Function(name, params, body) => {
-                let f = LoxFunction::new(name, params, body);
+                let f = LoxFunction::new(name, params, body, Rc::clone(&self.environment));
                 self.environment
+                    .borrow_mut()
                     .define(name.lexeme.clone(), Value::Function(Box::new(f)));
             }
-            Stmt::Print(expr) => {
-                if let Ok(result) = self.evaluate(&*expr) {
-                    println!("{}", result);
+            Stmt::Import(keyword, path, alias) => {
+                self.execute_import(keyword, path, alias.as_ref())?;
+            }
+            Stmt::ImportFrom(keyword, path, names) => {
+                self.execute_import_from(keyword, path, names)?;
+            }
+            Stmt::Print(keyword, expr) => {
+                let result = self.evaluate(expr)?;
+                writeln!(self.output, "{result}")
+                    .map_err(|e| RuntimeError::Io(e.to_string(), keyword.line))?;
+            }
+            Stmt::Throw(keyword, value) => {
+                let value = self.evaluate(value)?;
+
+                // errors created by the program get the line they are thrown on
+                if let Value::Instance(instance) = &value {
+                    let mut instance = instance.borrow_mut();
+                    if Rc::ptr_eq(instance.class(), &self.error_class)
+                        && instance.get_field("line") == Some(Value::Nil)
+                    {
+                        #[allow(clippy::cast_precision_loss)]
+                        instance.set("line", Value::Number(keyword.line as f64));
+                    }
                 }
+
+                return Err(RuntimeError::Throw(value, keyword.line));
+            }
+            Stmt::Try(body, catch, finally) => {
+                self.execute_try(body, catch.as_ref(), finally.as_deref())?;
             }
             Stmt::Return(_, value) => {
                 let return_value = match value {
@@ -67,47 +540,309 @@ impl Interpreter {
 
                 return Err(RuntimeError::Return(return_value));
             }
-            Stmt::Var(name, None) => self.environment.define(name.lexeme.clone(), Value::Nil),
+            Stmt::Var(name, None) => {
+                self.allocate(Allocation::Variable(name.lexeme.len()), name.line)?;
+                self.environment
+                    .borrow_mut()
+                    .define(name.lexeme.clone(), Value::Nil);
+            }
             Stmt::Var(name, Some(initializer)) => {
-                if let Ok(value) = self.evaluate(&*initializer) {
-                    self.environment.define(name.lexeme.clone(), value);
-                }
+                let value = self.evaluate(initializer)?;
+                self.allocate(Allocation::Variable(name.lexeme.len()), name.line)?;
+                self.environment
+                    .borrow_mut()
+                    .define(name.lexeme.clone(), value);
+            }
+            Stmt::While(keyword, condition, body, increment) => {
+                self.execute_while(keyword, condition, body, increment.as_ref())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn execute_block(
+        &mut self,
+        statements: &[Stmt],
+        env: Environment,
+    ) -> InterpretResult<()> {
+        let environments = self.memory_usage.environments;
+        self.memory_usage.add(Allocation::Environment);
+        let previous = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(env)));
+
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.execute(statement));
+
+        // restore the environment also if the block is left early, e.g. by a 'break'
+        let block = std::mem::replace(&mut self.environment, previous);
+        // unless a closure captured it, the environment is freed with the variables defined in
+        // it, and with the environments of nested blocks, as they can only outlive the block
+        // through a closure too
+        if Rc::strong_count(&block) == 1 {
+            self.memory_usage.environments = environments.min(self.memory_usage.environments);
+        }
+
+        result
+    }
+
+    fn execute_while(
+        &mut self,
+        keyword: &Token,
+        condition: &Expr,
+        body: &Stmt,
+        increment: Option<&Expr>,
+    ) -> InterpretResult<()> {
+        while self.evaluate(condition)?.is_truthy() {
+            self.check_limits(keyword.line)?;
+
+            match self.execute(body) {
+                Ok(()) | Err(RuntimeError::Continue) => {}
+                Err(RuntimeError::Break) => break,
+                Err(e) => return Err(e),
             }
-            Stmt::While(condition, body) => {
-                self.execute_while(condition, body)?;
+
+            if let Some(increment) = increment {
+                self.evaluate(increment)?;
             }
         }
 
         Ok(())
     }
 
-    pub fn execute_block(&mut self, statements: &[Stmt], env: &Environment) -> InterpretResult<()> {
-        self.environment = Environment::new_with_parent(env.clone());
+    fn execute_import(
+        &mut self,
+        keyword: &Token,
+        path: &str,
+        alias: Option<&Token>,
+    ) -> InterpretResult<()> {
+        let module = self.import(path, keyword.line)?;
+        let name = alias.map_or_else(|| module.name().to_string(), |alias| alias.lexeme.clone());
 
-        for statement in statements {
-            self.execute(statement)?;
+        self.environment
+            .borrow_mut()
+            .define(name, Value::Module(module));
+
+        Ok(())
+    }
+
+    fn execute_import_from(
+        &mut self,
+        keyword: &Token,
+        path: &str,
+        names: &[Token],
+    ) -> InterpretResult<()> {
+        let module = self.import(path, keyword.line)?;
+
+        // all names are resolved before any of them is defined
+        let values = names
+            .iter()
+            .map(|name| module.get(name))
+            .collect::<InterpretResult<Vec<Value>>>()?;
+
+        for (name, value) in names.iter().zip(values) {
+            self.environment
+                .borrow_mut()
+                .define(name.lexeme.clone(), value);
+        }
+
+        Ok(())
+    }
+
+    // Returns the directory imports are resolved relative to
+    fn import_base(&self) -> &Path {
+        self.script_path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new(""))
+    }
+
+    // Checks that the names of 'from ... import' statements are exported before any code runs, so
+    // that imports in code that doesn't run are checked too. The imports of the imported modules
+    // are checked relative to them. Modules that can't be read are reported when they are
+    // imported, as are scripts without file access.
+    fn check_imports(
+        &self,
+        statements: &[Stmt],
+        base: &Path,
+        checked: &mut HashSet<PathBuf>,
+    ) -> InterpretResult<()> {
+        if !self.is_allowed(Capability::Fs) {
+            return Ok(());
         }
 
-        if let Some(parent) = self.environment.take_parent() {
-            self.environment = parent;
+        for (path, names) in lox_module::imports_from(statements) {
+            let Ok(full_path) = base.join(path).canonicalize() else {
+                continue;
+            };
+            let Ok(module_statements) = lox_module::load(&full_path) else {
+                continue;
+            };
+
+            let exports = lox_module::exports(&module_statements);
+            if let Some(name) = names.iter().find(|name| !exports.contains(&name.lexeme)) {
+                return Err(RuntimeError::NotExported(
+                    lox_module::name(&full_path),
+                    name.clone(),
+                ));
+            }
+
+            // every module is only checked once, which also stops at cyclic imports
+            if checked.insert(full_path.clone()) {
+                let module_base = full_path.parent().unwrap_or_else(|| Path::new(""));
+                self.check_imports(&module_statements, module_base, checked)?;
+            }
         }
 
         Ok(())
     }
 
-    fn execute_while(&mut self, condition: &Expr, body: &Stmt) -> InterpretResult<()> {
-        while self.evaluate(condition)?.is_truthy() {
-            self.execute(body)?;
+    // Returns the module with the given path, executing the module if it hasn't been imported yet
+    fn import(&mut self, path: &str, line: usize) -> InterpretResult<Rc<LoxModule>> {
+        // modules are read from files, so scripts without file access can't import them
+        self.require(Capability::Fs, "import", line)?;
+
+        let full_path = self
+            .import_base()
+            .join(path)
+            .canonicalize()
+            .map_err(|e| RuntimeError::ImportFailed(path.to_string(), e.to_string(), line))?;
+
+        if let Some(module) = self.modules.get(&full_path) {
+            return Ok(Rc::clone(module));
+        }
+
+        if self.imports.contains(&full_path) {
+            return Err(RuntimeError::CyclicImport(path.to_string(), line));
+        }
+
+        let statements = lox_module::load(&full_path)
+            .map_err(|reason| RuntimeError::ImportFailed(path.to_string(), reason, line))?;
+
+        let env = Rc::new(RefCell::new(Environment::new_with_parent(Rc::clone(
+            &self.globals,
+        ))));
+        let previous_env = std::mem::replace(&mut self.environment, Rc::clone(&env));
+        let previous_path = self.script_path.replace(full_path.clone());
+        self.imports.push(full_path.clone());
+
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.execute(statement));
+
+        self.imports.pop();
+        self.script_path = previous_path;
+        self.environment = previous_env;
+        result?;
+
+        let name = lox_module::name(&full_path);
+        let module = Rc::new(LoxModule::new(&name, env, &statements));
+        self.modules.insert(full_path, Rc::clone(&module));
+
+        Ok(module)
+    }
+
+    fn execute_try(
+        &mut self,
+        body: &[Stmt],
+        catch: Option<&(Token, Vec<Stmt>)>,
+        finally: Option<&[Stmt]>,
+    ) -> InterpretResult<()> {
+        let env = Environment::new_with_parent(Rc::clone(&self.environment));
+        let mut result = self.execute_block(body, env);
+
+        // exceeded limits stop the program without running the 'finally' clause, which could
+        // keep it running
+        if matches!(&result, Err(error) if error.is_limit()) {
+            return result;
+        }
+
+        if let (Err(error), Some((name, handler))) = (&result, catch) {
+            if error.is_exception() {
+                self.stack_trace.clear();
+
+                let mut env = Environment::new_with_parent(Rc::clone(&self.environment));
+                env.define(name.lexeme.clone(), self.error_value(result.unwrap_err()));
+                result = self.execute_block(handler, env);
+            }
+        }
+
+        // errors, 'return', 'break' and 'continue' in the 'finally' clause take precedence
+        if let Some(finally) = finally {
+            let env = Environment::new_with_parent(Rc::clone(&self.environment));
+            self.execute_block(finally, env)?;
+        }
+
+        result
+    }
+
+    // Converts a caught exception to the value bound to the variable of the 'catch' clause
+    fn error_value(&self, error: RuntimeError) -> Value {
+        if let RuntimeError::Throw(value, _) = error {
+            return value;
+        }
+
+        let mut instance = LoxInstance::new(Rc::clone(&self.error_class));
+        instance.set("message", Value::String(error.message()));
+        #[allow(clippy::cast_precision_loss)]
+        instance.set(
+            "line",
+            error
+                .line()
+                .map_or(Value::Nil, |line| Value::Number(line as f64)),
+        );
+
+        Value::Instance(Rc::new(RefCell::new(instance)))
+    }
+
+    fn execute_for_in(
+        &mut self,
+        name: &Token,
+        iterable: &Expr,
+        body: &Stmt,
+    ) -> InterpretResult<()> {
+        let iterable = self.evaluate(iterable)?;
+        let mut iterator = LoxIterator::new(self, iterable, name.line)?;
+
+        while let Some(value) = iterator.next(self, name.line)? {
+            self.check_limits(name.line)?;
+
+            // every iteration gets its own variable, so closures capture the current value
+            let mut env = Environment::new_with_parent(Rc::clone(&self.environment));
+            env.define(name.lexeme.clone(), value);
+
+            match self.execute_block(std::slice::from_ref(body), env) {
+                Ok(()) | Err(RuntimeError::Continue) => {}
+                Err(RuntimeError::Break) => break,
+                Err(e) => return Err(e),
+            }
         }
 
         Ok(())
     }
 
     fn evaluate(&mut self, expr: &Expr) -> InterpretResult<Value> {
+        // long chains of operators, calls and property accesses are parsed without recursion, but
+        // evaluated with it
+        if let Expr::Binary {
+            operator: token, ..
+        }
+        | Expr::Logical {
+            operator: token, ..
+        }
+        | Expr::Call { paren: token, .. }
+        | Expr::Get { name: token, .. }
+        | Expr::GetIndex { bracket: token, .. } = expr
+        {
+            self.check_stack(token.line)?;
+        }
+
         match expr {
             Expr::Assign { name, value } => {
-                let v = self.evaluate(&*value)?;
-                self.environment.assign(name.lexeme.clone(), v.clone());
+                let v = self.evaluate(value)?;
+                self.environment
+                    .borrow_mut()
+                    .assign(name.lexeme.clone(), v.clone());
                 Ok(v)
             }
             Expr::Binary {
@@ -119,34 +854,51 @@ impl Interpreter {
                 callee,
                 paren,
                 arguments,
-            } => {
-                let callee = self.evaluate(callee)?;
-
-                match callee {
-                    Value::Function(callable) => {
-                        let mut args = Vec::with_capacity(arguments.len());
-
-                        for argument in arguments {
-                            args.push(self.evaluate(argument)?);
-                        }
+            } => self.evaluate_call(callee, paren, arguments),
+            Expr::Get { object, name } => self.evaluate_get(object, name),
+            Expr::GetIndex {
+                object,
+                bracket,
+                index,
+            } => self.evaluate_get_index(object, bracket, index),
+            Expr::Grouping { expression: expr } => self.evaluate(expr),
+            Expr::Lambda { params, body, .. } => {
+                let f = LoxFunction::new_lambda(params, body, Rc::clone(&self.environment));
+                Ok(Value::Function(Box::new(f)))
+            }
+            Expr::List { elements } => {
+                let mut values = Vec::with_capacity(elements.len());
 
-                        Ok(callable.call(self, args))
-                    }
-                    _ => Err(RuntimeError::ValueNotCallable(callee)),
+                for element in elements {
+                    values.push(self.evaluate(element)?);
                 }
+                // list literals have no line to report, the limit is checked on the next call or
+                // loop iteration
+                self.memory_usage.add(Allocation::List(values.len()));
+
+                Ok(Value::List(Rc::new(RefCell::new(values))))
             }
-            Expr::Get { object, name } => Err(RuntimeError::InvalidOperator(name.clone())), // TODO implement
-            Expr::Grouping { expression: expr } => self.evaluate(&*expr),
             Expr::Literal(Literal::Bool(bool)) => Ok(Value::Bool(*bool)),
             Expr::Literal(Literal::Nil) => Ok(Value::Nil),
             Expr::Literal(Literal::Number(number)) => Ok(Value::Number(*number)),
             Expr::Literal(Literal::String(string)) => Ok(Value::String(string.clone())),
+            Expr::Map { brace, entries } => {
+                self.allocate(Allocation::Map(entries.len()), brace.line)?;
+                let mut m = LoxMap::new();
+
+                for (key, value) in entries {
+                    let key = map::key(&self.evaluate(key)?, brace.line)?;
+                    m.insert(key, self.evaluate(value)?);
+                }
+
+                Ok(Value::Map(Rc::new(RefCell::new(m))))
+            }
             Expr::Logical {
                 left,
                 operator,
                 right,
             } => {
-                let left_result = self.evaluate(&*left)?;
+                let left_result = self.evaluate(left)?;
 
                 // short-circuit, if possible
                 if operator.token_type == TokenType::Or {
@@ -157,81 +909,255 @@ impl Interpreter {
                     return Ok(left_result);
                 }
 
-                Ok(self.evaluate(&*right)?)
+                Ok(self.evaluate(right)?)
             }
             Expr::Set {
                 object,
                 name,
                 value,
-            } => Err(RuntimeError::InvalidOperator(name.clone())), // TODO implement
-            Expr::This(keyword) => Err(RuntimeError::InvalidOperator(keyword.clone())), // TODO implement
-            Expr::Unary { operator, right } => self.evaluate_unary(operator, &*right),
-            Expr::Variable(name) => self.environment.get(name.lexeme.clone()),
-        }
-    }
-
-    fn evaluate_unary(&mut self, operator: &Token, right: &Expr) -> InterpretResult<Value> {
-        let result = self.evaluate(right)?;
-
-        match operator.token_type {
-            TokenType::Minus => match result {
-                Value::Number(number) => Ok(Value::Number(-number)),
-                _ => Err(RuntimeError::NumberExpectedAfterMinus(operator.line)),
+            } => match self.evaluate(object)? {
+                Value::Instance(instance) => {
+                    let value = self.evaluate(value)?;
+                    instance.borrow_mut().set(&name.lexeme, value.clone());
+                    Ok(value)
+                }
+                _ => Err(RuntimeError::OnlyInstancesHaveFields(name.clone())),
             },
-            TokenType::Bang => Ok(Value::Bool(!&result.is_truthy())),
-            _ => Err(RuntimeError::InvalidOperator(operator.clone())),
+            Expr::SetIndex {
+                object,
+                bracket,
+                index,
+                value,
+            } => self.evaluate_set_index(object, bracket, index, value),
+            Expr::This(keyword) => self.environment.borrow().get(keyword),
+            Expr::Unary { operator, right } => self.evaluate_unary(operator, right),
+            Expr::Variable(name) => self.environment.borrow().get(name),
         }
     }
 
-    #[allow(clippy::float_cmp)]
-    fn evaluate_binary(
+    fn evaluate_call(
         &mut self,
-        left: &Expr,
-        operator: &Token,
-        right: &Expr,
+        callee: &Expr,
+        paren: &Token,
+        arguments: &[Expr],
     ) -> InterpretResult<Value> {
-        match (self.evaluate(left)?, self.evaluate(right)?) {
-            (Value::Number(l), Value::Number(r)) => match operator.token_type {
-                TokenType::Plus => Ok(Value::Number(l + r)),
-                TokenType::Minus => Ok(Value::Number(l - r)),
-                TokenType::Star => Ok(Value::Number(l * r)),
-                TokenType::Slash => Ok(Value::Number(l / r)),
-                TokenType::Greater => Ok(Value::Bool(l > r)),
-                TokenType::GreaterEqual => Ok(Value::Bool(l >= r)),
-                TokenType::Less => Ok(Value::Bool(l < r)),
-                TokenType::LessEqual => Ok(Value::Bool(l <= r)),
-                TokenType::EqualEqual => Ok(Value::Bool(l == r)),
-                TokenType::BangEqual => Ok(Value::Bool(l != r)),
-                _ => Err(RuntimeError::InvalidOperator(operator.clone())),
-            },
-            (Value::String(l), Value::String(r)) => match operator.token_type {
-                TokenType::Plus => Ok(Value::String(format!("{}{}", l, r))),
-                TokenType::EqualEqual => Ok(Value::Bool(l == r)),
-                TokenType::BangEqual => Ok(Value::Bool(l != r)),
-                _ => Err(RuntimeError::InvalidOperator(operator.clone())),
-            },
-            (Value::Bool(l), Value::Bool(r)) => match operator.token_type {
-                TokenType::EqualEqual => Ok(Value::Bool(l == r)),
-                TokenType::BangEqual => Ok(Value::Bool(l != r)),
-                _ => Err(RuntimeError::InvalidOperator(operator.clone())),
-            },
-            (Value::Nil, Value::Nil) => match operator.token_type {
-                TokenType::EqualEqual => Ok(Value::Bool(true)),
-                TokenType::BangEqual => Ok(Value::Bool(false)),
-                _ => Err(RuntimeError::InvalidOperator(operator.clone())),
-            },
-            _ => match operator.token_type {
-                TokenType::EqualEqual => Ok(Value::Bool(false)),
-                TokenType::BangEqual => Ok(Value::Bool(true)),
-                _ => Err(RuntimeError::InvalidOperator(operator.clone())),
-            },
+        let callee = self.evaluate(callee)?;
+
+        let mut args = Vec::with_capacity(arguments.len());
+
+        for argument in arguments {
+            args.push(self.evaluate(argument)?);
         }
+
+        self.call_value(callee, args, paren.line)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // Fails if the native stack of the current run grew beyond its limit, as the interpreter
+    // recurses for calls and nested expressions
+    fn check_stack(&self, line: usize) -> InterpretResult<()> {
+        if let Some(max) = self.limits.max_stack {
+            if stack_address().abs_diff(self.stack_base) > max {
+                return Err(RuntimeError::StackLimitExceeded(max, line));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Calls a function or class after checking the arguments and limits, the call is recorded
+    // for stack traces
+    fn call_value(
+        &mut self,
+        callee: Value,
+        args: Vec<Value>,
+        line: usize,
+    ) -> InterpretResult<Value> {
+        let (name, arity) = match &callee {
+            Value::Class(class) => (class.name().to_string(), class.arity()),
+            Value::Function(callable) => (callable.name().to_string(), callable.arity()),
+            _ => return Err(RuntimeError::ValueNotCallable(callee, line)),
+        };
+
+        if args.len() != arity {
+            return Err(RuntimeError::ArityMismatch(arity, args.len(), line));
+        }
+
+        self.check_limits(line)?;
+        if let Some(max) = self.limits.max_call_depth {
+            if self.call_stack.len() >= max {
+                return Err(RuntimeError::CallDepthExceeded(max, line));
+            }
+        }
+        self.check_stack(line)?;
+
+        self.call_stack.push((name, line));
+        let result = self.invoke(callee, args, line);
+
+        // the stack trace is recorded by the innermost call, before the stack unwinds
+        if let Err(error) = &result {
+            if error.line().is_some() && self.stack_trace.is_empty() {
+                self.stack_trace = self.call_stack.iter().rev().cloned().collect();
+            }
+        }
+        self.call_stack.pop();
+
+        result
+    }
+
+    fn invoke(&mut self, callee: Value, args: Vec<Value>, line: usize) -> InterpretResult<Value> {
+        match callee {
+            Value::Class(class) => {
+                let instance =
+                    Value::Instance(Rc::new(RefCell::new(LoxInstance::new(Rc::clone(&class)))));
+
+                if let Some(initializer) = class.find_method("init") {
+                    initializer.bind(instance.clone()).call(self, args, line)?;
+                }
+
+                Ok(instance)
+            }
+            Value::Function(callable) => callable.call(self, args, line),
+            _ => unreachable!(),
+        }
+    }
+
+    // Looks up a property, methods of built-in values are bound to the value
+    fn evaluate_get(&mut self, object: &Expr, name: &Token) -> InterpretResult<Value> {
+        match self.evaluate(object)? {
+            Value::Instance(instance) => LoxInstance::get(&instance, name),
+            Value::List(list) => list::method(&name.lexeme).map_or_else(
+                || Err(RuntimeError::UndefinedProperty(name.clone())),
+                |method| Ok(Value::Function(Box::new(method.bind(Value::List(list))))),
+            ),
+            Value::Map(m) => map::method(&name.lexeme).map_or_else(
+                || Err(RuntimeError::UndefinedProperty(name.clone())),
+                |method| Ok(Value::Function(Box::new(method.bind(Value::Map(m))))),
+            ),
+            Value::Module(module) => module.get(name),
+            Value::String(s) => string::method(&name.lexeme).map_or_else(
+                || Err(RuntimeError::UndefinedProperty(name.clone())),
+                |method| Ok(Value::Function(Box::new(method.bind(Value::String(s))))),
+            ),
+            _ => Err(RuntimeError::UndefinedProperty(name.clone())),
+        }
+    }
+
+    fn evaluate_get_index(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+    ) -> InterpretResult<Value> {
+        let object = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+
+        match object {
+            Value::List(list) => {
+                let list = list.borrow();
+                let i = list::index(&index, list.len(), bracket.line)?;
+                Ok(list[i].clone())
+            }
+            // reading a missing key results in nil
+            Value::Map(m) => {
+                let key = map::key(&index, bracket.line)?;
+                Ok(m.borrow().get(&key).cloned().unwrap_or(Value::Nil))
+            }
+            Value::String(s) => string::char_at(self, &s, &index, bracket.line),
+            _ => Err(RuntimeError::ValueNotIndexable(object, bracket.line)),
+        }
+    }
+
+    fn evaluate_set_index(
+        &mut self,
+        object: &Expr,
+        bracket: &Token,
+        index: &Expr,
+        value: &Expr,
+    ) -> InterpretResult<Value> {
+        let object = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+        let value = self.evaluate(value)?;
+
+        match object {
+            Value::List(list) => {
+                let len = list.borrow().len();
+                let i = list::index(&index, len, bracket.line)?;
+                list.borrow_mut()[i] = value.clone();
+                Ok(value)
+            }
+            Value::Map(m) => {
+                let key = map::key(&index, bracket.line)?;
+                if !m.borrow().contains_key(&key) {
+                    self.allocate(Allocation::Map(1), bracket.line)?;
+                }
+                m.borrow_mut().insert(key, value.clone());
+                Ok(value)
+            }
+            _ => Err(RuntimeError::ValueNotIndexable(object, bracket.line)),
+        }
+    }
+
+    fn evaluate_unary(&mut self, operator: &Token, right: &Expr) -> InterpretResult<Value> {
+        let result = self.evaluate(right)?;
+
+        match operator.token_type {
+            TokenType::Minus => match result {
+                Value::Number(number) => Ok(Value::Number(-number)),
+                _ => Err(RuntimeError::NumberExpectedAfterMinus(operator.line)),
+            },
+            TokenType::Bang => Ok(Value::Bool(!&result.is_truthy())),
+            _ => Err(RuntimeError::InvalidOperator(operator.clone())),
+        }
+    }
+
+    fn evaluate_binary(
+        &mut self,
+        left: &Expr,
+        operator: &Token,
+        right: &Expr,
+    ) -> InterpretResult<Value> {
+        let (left, right) = (self.evaluate(left)?, self.evaluate(right)?);
+        match operator.token_type {
+            TokenType::EqualEqual => return Ok(Value::Bool(left == right)),
+            TokenType::BangEqual => return Ok(Value::Bool(left != right)),
+            _ => {}
+        }
+
+        match (left, right) {
+            (Value::Number(l), Value::Number(r)) => match operator.token_type {
+                TokenType::Plus => Ok(Value::Number(l + r)),
+                TokenType::Minus => Ok(Value::Number(l - r)),
+                TokenType::Star => Ok(Value::Number(l * r)),
+                TokenType::Slash => Ok(Value::Number(l / r)),
+                TokenType::Greater => Ok(Value::Bool(l > r)),
+                TokenType::GreaterEqual => Ok(Value::Bool(l >= r)),
+                TokenType::Less => Ok(Value::Bool(l < r)),
+                TokenType::LessEqual => Ok(Value::Bool(l <= r)),
+                _ => Err(RuntimeError::InvalidOperator(operator.clone())),
+            },
+            (Value::String(l), Value::String(r)) if operator.token_type == TokenType::Plus => {
+                self.allocate(Allocation::String(l.len() + r.len()), operator.line)?;
+                Ok(Value::String(format!("{l}{r}")))
+            }
+            _ => Err(RuntimeError::InvalidOperator(operator.clone())),
+        }
+    }
+}
+
+// Returns the current address of the native stack, the difference of two addresses is the stack
+// used by the calls in between
+fn stack_address() -> usize {
+    let marker = 0_u8;
+    std::ptr::addr_of!(marker) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+    use std::fs;
+    use std::time::Duration;
 
     #[test]
     fn evaluate_literals() {
@@ -283,8 +1209,8 @@ mod tests {
 
         let err = Interpreter::new().evaluate(&expr).unwrap_err();
         match err {
-            RuntimeError::NumberExpectedAfterMinus(_) => assert!(true),
-            _ => assert!(false),
+            RuntimeError::NumberExpectedAfterMinus(_) => {}
+            _ => panic!("unexpected error"),
         }
     }
 
@@ -426,7 +1352,7 @@ mod tests {
         const THREE: Literal = Literal::Number(3.0);
         const TRUE: Literal = Literal::Bool(true);
         const FALSE: Literal = Literal::Bool(false);
-        let empty: Literal = Literal::String("".to_string());
+        let empty: Literal = Literal::String(String::new());
         let bb: Literal = Literal::String("bb".to_string());
 
         let setup = vec![
@@ -439,7 +1365,7 @@ mod tests {
             (empty.clone(), bb, false),
             (NIL, ZERO, false),
             (NIL, FALSE, false),
-            (NIL, empty.clone(), false),
+            (NIL, empty, false),
         ];
 
         for (left, right, expected) in setup {
@@ -450,7 +1376,7 @@ mod tests {
             for (operator, expected) in operators {
                 let expr = Expr::Binary {
                     left: Box::new(Expr::Literal(left.clone())),
-                    operator: operator,
+                    operator,
                     right: Box::new(Expr::Literal(right.clone())),
                 };
 
@@ -467,7 +1393,7 @@ mod tests {
             token(TokenType::String("test".to_string())),
             Some(Expr::Literal(Literal::String("value".to_string()))),
         );
-        interpreter.interpret(vec![stmt]);
+        interpreter.interpret(&[stmt]).unwrap();
 
         let expr = Expr::Variable(token(TokenType::String("test".to_string())));
 
@@ -482,8 +1408,8 @@ mod tests {
         let error = Interpreter::new().evaluate(&expr).unwrap_err();
 
         match error {
-            RuntimeError::UndefinedVariable(_) => assert!(true),
-            _ => assert!(false),
+            RuntimeError::UndefinedVariable(_) => {}
+            _ => panic!("unexpected error"),
         }
     }
 
@@ -498,7 +1424,7 @@ mod tests {
             name: token(TokenType::String("test".to_string())),
             value: Box::new(Expr::Literal(Literal::String("updated".to_string()))),
         });
-        interpreter.interpret(vec![def_stmt, assign_stmt]);
+        interpreter.interpret(&[def_stmt, assign_stmt]).unwrap();
 
         let expr = Expr::Variable(token(TokenType::String("test".to_string())));
 
@@ -506,6 +1432,1023 @@ mod tests {
         assert_eq!(Value::String("updated".to_string()), result);
     }
 
+    #[test]
+    fn evaluate_function_equality() {
+        let mut interpreter = Interpreter::new();
+        let f = Stmt::Function(
+            token(TokenType::Identifier("f".to_string())),
+            vec![],
+            vec![],
+        );
+        let g = Stmt::Function(
+            Token::new(TokenType::Identifier("g".to_string()), 2),
+            vec![],
+            vec![],
+        );
+        interpreter.interpret(&[f, g]).unwrap();
+
+        let setup = vec![
+            ("f", "f", true),
+            ("f", "g", false),
+            ("clock", "clock", true),
+        ];
+
+        for (left, right, expected) in setup {
+            let expr = Expr::Binary {
+                left: Box::new(Expr::Variable(token(TokenType::Identifier(
+                    left.to_string(),
+                )))),
+                operator: token(TokenType::EqualEqual),
+                right: Box::new(Expr::Variable(token(TokenType::Identifier(
+                    right.to_string(),
+                )))),
+            };
+
+            let result = interpreter.evaluate(&expr).unwrap();
+            assert_eq!(Value::Bool(expected), result);
+        }
+    }
+
+    #[test]
+    fn execute_while_with_break_and_continue() {
+        let interpreter = run(r"
+            var i = 0;
+            var sum = 0;
+            while (true) {
+                i = i + 1;
+                if (i == 2) continue;
+                if (i > 4) break;
+                sum = sum + i;
+            }");
+
+        assert_eq!(Value::Number(5.0), get(&interpreter, "i"));
+        assert_eq!(Value::Number(8.0), get(&interpreter, "sum"));
+    }
+
+    #[test]
+    fn execute_for_with_continue() {
+        let interpreter = run(r"
+            var sum = 0;
+            for (var i = 0; i < 5; i = i + 1) {
+                if (i == 2) continue;
+                sum = sum + i;
+            }");
+
+        assert_eq!(Value::Number(8.0), get(&interpreter, "sum"));
+    }
+
+    #[test]
+    fn execute_function_modifying_global() {
+        let interpreter = run(r"
+            var count = 0;
+            fun increment() {
+                count = count + 1;
+                return count;
+            }
+            increment();
+            var result = increment();");
+
+        assert_eq!(Value::Number(2.0), get(&interpreter, "count"));
+        assert_eq!(Value::Number(2.0), get(&interpreter, "result"));
+    }
+
+    #[test]
+    fn evaluate_lambda() {
+        let interpreter = run(r"
+            fun apply(f, x) {
+                return f(x);
+            }
+            var a = apply(fun (x) { return x + 1; }, 1);
+            var b = apply(fun (x) => x * 3, 2);");
+
+        assert_eq!(Value::Number(2.0), get(&interpreter, "a"));
+        assert_eq!(Value::Number(6.0), get(&interpreter, "b"));
+    }
+
+    #[test]
+    fn evaluate_closure() {
+        let interpreter = run(r"
+            fun make_counter() {
+                var i = 0;
+                return fun () {
+                    i = i + 1;
+                    return i;
+                };
+            }
+            var counter = make_counter();
+            counter();
+            var result = counter();");
+
+        assert_eq!(Value::Number(2.0), get(&interpreter, "result"));
+    }
+
+    #[test]
+    fn evaluate_list_operations() {
+        let interpreter = run(r"
+            var xs = [3, 1];
+            xs.push(2);
+            xs.sort();
+            xs[0] = xs[0] + 10;
+            var len = xs.len();
+            var last = xs.pop();
+            var copy = xs;
+            copy.push(4);");
+
+        assert_eq!(Value::Number(3.0), get(&interpreter, "len"));
+        assert_eq!(Value::Number(3.0), get(&interpreter, "last"));
+        assert_eq!(
+            "[11, 2, 4]",
+            get(&interpreter, "xs").to_string(),
+            "lists should have reference semantics"
+        );
+    }
+
+    #[test]
+    fn evaluate_self_containing_list() {
+        let interpreter = run(r"
+            var xs = [1];
+            xs.push(xs);
+            var string = str(xs);
+            var contains = xs.contains(xs);
+            var index = xs.index_of(xs);
+            var equal_contains = [[1]].contains([1]);
+            var equal_index = [[1]].index_of([1]);
+            xs.pop();");
+
+        assert_eq!(
+            Value::String("[1, [...]]".to_string()),
+            get(&interpreter, "string")
+        );
+        assert_eq!(Value::Bool(true), get(&interpreter, "contains"));
+        assert_eq!(Value::Number(1.0), get(&interpreter, "index"));
+        // like ==, lists are only found if they are the same list
+        assert_eq!(Value::Bool(false), get(&interpreter, "equal_contains"));
+        assert_eq!(Value::Nil, get(&interpreter, "equal_index"));
+    }
+
+    #[test]
+    fn evaluate_string_operations() {
+        let interpreter = run(r#"
+            var s = "  Hello, Wörld ";
+            var trimmed = s.trim();
+            var words = trimmed.split(", ");
+            var joined = words.join("-").upper();
+            var replaced = trimmed.replace("l", "L").lower();
+            var checks = [trimmed.starts_with("He"), trimmed.ends_with("d"), s.contains("x")];
+            var second = trimmed[1];
+            var repeated = "ab".repeat(2);"#);
+
+        assert_eq!("[Hello, Wörld]", get(&interpreter, "words").to_string());
+        assert_eq!("HELLO-WÖRLD", get(&interpreter, "joined").to_string());
+        assert_eq!("hello, wörld", get(&interpreter, "replaced").to_string());
+        assert_eq!(
+            "[true, true, false]",
+            get(&interpreter, "checks").to_string()
+        );
+        assert_eq!("e", get(&interpreter, "second").to_string());
+        assert_eq!("abab", get(&interpreter, "repeated").to_string());
+    }
+
+    #[test]
+    fn evaluate_too_large_repeat() {
+        for count in ["100000000", "100000000000000000000"] {
+            let error = run_with_error(&format!("var s = \"ab\".repeat({count});"));
+
+            match error {
+                RuntimeError::InvalidArgument(_, 1) => {}
+                _ => panic!("unexpected result"),
+            }
+        }
+    }
+
+    #[test]
+    fn evaluate_conversions_and_introspection() {
+        let interpreter = run(r#"
+            class A {}
+            class B {}
+            var a = A();
+            var types = [type(1), type("s"), type(nil), type(true), type([]), type({}), type(A), type(a), type(str)];
+            var instances = [is_instance(a, A), is_instance(a, B), is_instance(1, A)];
+            var strings = [str(1.5), str([1, "a"]), str(nil)];
+            var number = num("42") + 1;"#);
+
+        assert_eq!(
+            "[number, string, nil, bool, list, map, class, instance, function]",
+            get(&interpreter, "types").to_string()
+        );
+        assert_eq!(
+            "[true, false, false]",
+            get(&interpreter, "instances").to_string()
+        );
+        assert_eq!(
+            "[1.5, [1, a], nil]",
+            get(&interpreter, "strings").to_string()
+        );
+        assert_eq!(Value::Number(43.0), get(&interpreter, "number"));
+    }
+
+    #[test]
+    fn evaluate_too_large_range() {
+        let error = run_with_error("var r = range(0, 100000000000000000000000);");
+
+        match error {
+            RuntimeError::InvalidArgument(_, 1) => {}
+            _ => panic!("unexpected result"),
+        }
+    }
+
+    #[test]
+    fn evaluate_map_operations() {
+        let interpreter = run(r#"
+            var m = {"b": 1, 2: "two"};
+            m["a"] = m["b"] + 1;
+            m["b"] = nil;
+            var missing = m["missing"];
+            var has = m.has(2);
+            var removed = m.remove(2);
+            var len = m.len();
+            var keys = m.keys();
+            var values = m.values();"#);
+
+        assert_eq!(Value::Nil, get(&interpreter, "missing"));
+        assert_eq!(Value::Bool(true), get(&interpreter, "has"));
+        assert_eq!(
+            Value::String("two".to_string()),
+            get(&interpreter, "removed")
+        );
+        assert_eq!(Value::Number(2.0), get(&interpreter, "len"));
+        assert_eq!("[b, a]", get(&interpreter, "keys").to_string());
+        assert_eq!("[nil, 2]", get(&interpreter, "values").to_string());
+    }
+
+    #[test]
+    fn evaluate_self_containing_map() {
+        let interpreter = run(r#"
+            var m = {"a": 1};
+            m["b"] = m;
+            var string = str(m);
+            var values = str(m.values());
+            m.remove("b");"#);
+
+        assert_eq!(
+            Value::String("{a: 1, b: {...}}".to_string()),
+            get(&interpreter, "string")
+        );
+        assert_eq!(
+            Value::String("[1, {a: 1, b: {...}}]".to_string()),
+            get(&interpreter, "values")
+        );
+    }
+
+    #[test]
+    fn evaluate_map_with_invalid_key() {
+        let error = run_with_error("var m = {};\nm[[]] = 1;");
+
+        match error {
+            RuntimeError::InvalidKey(_, line) => assert_eq!(2, line),
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn evaluate_list_index_out_of_bounds() {
+        let error = run_with_error("var xs = [1];\nxs[1];");
+
+        match error {
+            RuntimeError::IndexOutOfBounds(index, len, line) => {
+                assert_eq!((1.0, 1, 2), (index, len, line));
+            }
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn evaluate_class_with_initializer_and_methods() {
+        let interpreter = run(r"
+            class Point {
+                init(x, y) {
+                    this.x = x;
+                    this.y = y;
+                }
+                sum() {
+                    return this.x + this.y;
+                }
+            }
+            var p = Point(1, 2);
+            p.x = 10;
+            var sum = p.sum();");
+
+        assert_eq!(Value::Number(12.0), get(&interpreter, "sum"));
+        assert_eq!("Point instance", get(&interpreter, "p").to_string());
+    }
+
+    #[test]
+    fn set_field_on_non_instance() {
+        let error = run_with_error("var xs = [];\nxs.field = 1;");
+
+        match error {
+            RuntimeError::OnlyInstancesHaveFields(token) => assert_eq!(2, token.line),
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn execute_for_in_over_builtin_values() {
+        let interpreter = run(r#"
+            var numbers = 0;
+            for (var x in [1, 2, 3]) numbers = numbers + x;
+            var keys = "";
+            for (var k in {"a": 1, "b": 2}) keys = keys + k;
+            var chars = "";
+            for (var c in "hé!") chars = c + chars;
+            var sum = 0;
+            for (var i in range(0, 10)) {
+                if (i == 2) continue;
+                if (i == 5) break;
+                sum = sum + i;
+            }"#);
+
+        assert_eq!(Value::Number(6.0), get(&interpreter, "numbers"));
+        assert_eq!(Value::String("ab".to_string()), get(&interpreter, "keys"));
+        assert_eq!(Value::String("!éh".to_string()), get(&interpreter, "chars"));
+        assert_eq!(Value::Number(8.0), get(&interpreter, "sum"));
+    }
+
+    #[test]
+    fn execute_for_in_over_instances() {
+        let interpreter = run(r"
+            class Counter {
+                init(n) {
+                    this.i = 0;
+                    this.n = n;
+                }
+                has_next() {
+                    return this.i < this.n;
+                }
+                next() {
+                    this.i = this.i + 1;
+                    return this.i;
+                }
+            }
+            class Pair {
+                iterator() {
+                    return Counter(2);
+                }
+            }
+            var counted = [];
+            for (var x in Counter(3)) counted.push(x);
+            var paired = [];
+            for (var x in Pair()) paired.push(x);");
+
+        assert_eq!("[1, 2, 3]", get(&interpreter, "counted").to_string());
+        assert_eq!("[1, 2]", get(&interpreter, "paired").to_string());
+    }
+
+    #[test]
+    fn execute_for_in_over_non_iterable() {
+        let error = run_with_error("for (var x in 1) print x;");
+
+        match error {
+            RuntimeError::ValueNotIterable(Value::Number(_), 1) => {}
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn execute_try_with_thrown_values() {
+        let interpreter = run(r#"
+            var log = [];
+            try {
+                throw Error("failed");
+            } catch (e) {
+                log.push(e.message);
+                log.push(e.line);
+            } finally {
+                log.push("finally");
+            }
+            try {
+                throw "plain";
+            } catch (e) {
+                log.push(e);
+            }
+            fun f() {
+                try {
+                    return 1;
+                } finally {
+                    log.push("cleanup");
+                }
+            }
+            var result = f();"#);
+
+        assert_eq!(
+            "[failed, 4, finally, plain, cleanup]",
+            get(&interpreter, "log").to_string()
+        );
+        assert_eq!(Value::Number(1.0), get(&interpreter, "result"));
+    }
+
+    #[test]
+    fn execute_try_with_builtin_error() {
+        let interpreter = run(r"
+            var message;
+            var line;
+            try {
+                [].pop();
+            } catch (e) {
+                message = e.message;
+                line = e.line;
+            }");
+
+        assert_eq!(
+            Value::String("List is empty".to_string()),
+            get(&interpreter, "message")
+        );
+        assert_eq!(Value::Number(5.0), get(&interpreter, "line"));
+    }
+
+    #[test]
+    fn uncaught_exception_with_stack_trace() {
+        let statements = Parser::new()
+            .parse(Scanner::scan("fun f() {\nthrow 1;\n}\nfun g() { f(); }\ng();").unwrap())
+            .unwrap();
+        let mut interpreter = Interpreter::new();
+        let error = interpreter.interpret(&statements).unwrap_err();
+
+        match error {
+            RuntimeError::Throw(Value::Number(_), 2) => {}
+            _ => panic!("unexpected error"),
+        }
+        assert_eq!(
+            &[("f".to_string(), 4), ("g".to_string(), 5)],
+            interpreter.stack_trace()
+        );
+    }
+
+    #[test]
+    fn execute_imports() {
+        let dir = module_dir("imports");
+        fs::write(
+            dir.join("lib/util.lox"),
+            "export var items = [];\nexport fun double(x) { return x * 2; }",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("lib/other.lox"),
+            "import \"util.lox\";\nutil.items.push(1);",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("main.lox"),
+            r#"
+            import "lib/util.lox";
+            import "lib/util.lox" as u;
+            import "lib/other.lox" as other;
+            var doubled = util.double(21);
+            var same = u == util;
+            var items = util.items;"#,
+        )
+        .unwrap();
+
+        let interpreter = run_file(&dir.join("main.lox")).unwrap();
+
+        assert_eq!(Value::Number(42.0), get(&interpreter, "doubled"));
+        assert_eq!(Value::Bool(true), get(&interpreter, "same"));
+        assert_eq!(
+            "[1]",
+            get(&interpreter, "items").to_string(),
+            "modules should only be executed once"
+        );
+        assert_eq!("<module other>", get(&interpreter, "other").to_string());
+    }
+
+    #[test]
+    fn execute_cyclic_imports() {
+        let dir = module_dir("cyclic_imports");
+        fs::write(dir.join("main.lox"), "import \"lib/a.lox\";").unwrap();
+        fs::write(dir.join("lib/a.lox"), "import \"b.lox\";").unwrap();
+        fs::write(dir.join("lib/b.lox"), "\nimport \"a.lox\";").unwrap();
+
+        match run_file(&dir.join("main.lox")).err() {
+            Some(RuntimeError::CyclicImport(path, 2)) => assert_eq!("a.lox", path),
+            _ => panic!("unexpected result"),
+        }
+
+        match run_file(&dir.join("lib/a.lox")).err() {
+            Some(RuntimeError::CyclicImport(path, 2)) => assert_eq!("a.lox", path),
+            _ => panic!("unexpected result"),
+        }
+    }
+
+    #[test]
+    fn execute_imports_of_exported_names() {
+        let dir = module_dir("exports");
+        fs::write(
+            dir.join("lib/math.lox"),
+            r"
+            fun square(x) { return x * x; }
+            export fun sum_of_squares(a, b) { return square(a) + square(b); }
+            export var zero = 0;
+            export class Point {}",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("main.lox"),
+            r#"
+            from "lib/math.lox" import sum_of_squares, zero;
+            import "lib/math.lox";
+            var sum = sum_of_squares(1, 2) + zero;
+            var point = math.Point();"#,
+        )
+        .unwrap();
+        let interpreter = run_file(&dir.join("main.lox")).unwrap();
+
+        assert_eq!(Value::Number(5.0), get(&interpreter, "sum"));
+        assert_eq!("Point instance", get(&interpreter, "point").to_string());
+
+        // names imported with 'from' are checked before the script runs, also if the import
+        // itself never runs
+        for code in [
+            "\nfrom \"lib/math.lox\" import zero, square;",
+            "print 1;\nif (false) { from \"lib/math.lox\" import square; }",
+            "fun f() {\n  from \"lib/math.lox\" import square;\n}",
+            "import \"lib/math.lox\";\nmath.square(2);",
+        ] {
+            fs::write(dir.join("main.lox"), code).unwrap();
+
+            match run_file(&dir.join("main.lox")).err() {
+                Some(RuntimeError::NotExported(module, token)) => {
+                    assert_eq!(
+                        ("math", "square", 2),
+                        (module.as_str(), token.lexeme.as_str(), token.line)
+                    );
+                }
+                _ => panic!("unexpected result"),
+            }
+        }
+
+        // a module without 'export' exports nothing
+        fs::write(dir.join("lib/plain.lox"), "var x = 1;").unwrap();
+        fs::write(dir.join("main.lox"), "import \"lib/plain.lox\";\nplain.x;").unwrap();
+        match run_file(&dir.join("main.lox")).err() {
+            Some(RuntimeError::NotExported(module, token)) => {
+                assert_eq!(("plain", "x"), (module.as_str(), token.lexeme.as_str()));
+            }
+            _ => panic!("unexpected result"),
+        }
+    }
+
+    #[test]
+    fn execute_file_operations() {
+        let dir = module_dir("files");
+        let path = dir.join("lib/notes.txt");
+        let code = format!(
+            r#"
+            var path = "{}";
+            write_file(path, "a
+");
+            append_file(path, "b
+");
+            var content = read_file(path);
+            var lines = [];
+            for (var line in read_lines(path)) lines.push(line);
+            var entries = list_dir("{}");
+            remove_file(path);
+            var exists = file_exists(path);
+            var message;
+            try {{
+                read_file(path);
+            }} catch (e) {{
+                message = e.message;
+            }}"#,
+            path.display(),
+            dir.join("lib").display()
+        );
+        let interpreter = run(&code);
+
+        assert_eq!(
+            Value::String("a\nb\n".to_string()),
+            get(&interpreter, "content")
+        );
+        assert_eq!("[a, b]", get(&interpreter, "lines").to_string());
+        assert_eq!("[notes.txt]", get(&interpreter, "entries").to_string());
+        assert_eq!(Value::Bool(false), get(&interpreter, "exists"));
+        assert_ne!(Value::Nil, get(&interpreter, "message"));
+    }
+
+    #[test]
+    fn call_natives_without_capability() {
+        let statements = Parser::new()
+            .parse(Scanner::scan("clock();\nread_file(\"notes.txt\");").unwrap())
+            .unwrap();
+        let mut interpreter = Interpreter::with_capabilities(&[Capability::Time]);
+
+        match interpreter.interpret(&statements) {
+            Err(RuntimeError::CapabilityDenied(name, Capability::Fs, 2)) => {
+                assert_eq!("read_file", name);
+            }
+            _ => panic!("unexpected result"),
+        }
+
+        interpreter.deny(Capability::Time);
+        let error = interpreter.interpret(&statements).unwrap_err();
+        assert_eq!(
+            "'clock' requires the 'time' capability, which isn't allowed on line 1",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn evaluate_args_and_getenv() {
+        let statements = Parser::new()
+            .parse(
+                Scanner::scan("var args = args();\nvar missing = getenv(\"RLOX_UNSET_VARIABLE\");")
+                    .unwrap(),
+            )
+            .unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_script_args(&["a".to_string(), "b".to_string()]);
+        interpreter.interpret(&statements).unwrap();
+
+        assert_eq!("[a, b]", get(&interpreter, "args").to_string());
+        assert_eq!(Value::Nil, get(&interpreter, "missing"));
+    }
+
+    #[test]
+    fn execute_import_of_missing_module() {
+        let error = run_with_error("import \"missing.lox\";");
+
+        match error {
+            RuntimeError::ImportFailed(path, _, 1) => assert_eq!("missing.lox", path),
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn call_with_wrong_number_of_arguments() {
+        let error = run_with_error("fun f(a) {}\nf(1, 2);");
+
+        match error {
+            RuntimeError::ArityMismatch(expected, got, line) => {
+                assert_eq!((1, 2, 2), (expected, got, line));
+            }
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn limit_steps() {
+        let error = run_with_limits(
+            "var i = 0;\nwhile (true) i = i + 1;",
+            Limits {
+                max_steps: Some(100),
+                ..Limits::default()
+            },
+        );
+
+        match error {
+            RuntimeError::StepLimitExceeded(100, 2) => {}
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn limit_call_depth() {
+        let error = run_with_limits(
+            "fun f() { f(); }\nf();",
+            Limits {
+                max_call_depth: Some(20),
+                ..Limits::default()
+            },
+        );
+
+        match error {
+            RuntimeError::CallDepthExceeded(20, 1) => {}
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn limit_stack() {
+        let error = run_with_limits(
+            "fun f() { f(); }\nf();",
+            Limits {
+                max_call_depth: None,
+                max_stack: Some(64 * 1024),
+                ..Limits::default()
+            },
+        );
+
+        match error {
+            RuntimeError::StackLimitExceeded(65_536, 1) => {}
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn limit_time() {
+        let error = run_with_limits(
+            "for (var x in range(0, 10)) { while (true) {} }",
+            Limits {
+                timeout: Some(Duration::from_millis(10)),
+                ..Limits::default()
+            },
+        );
+
+        match error {
+            RuntimeError::Timeout(timeout, 1) => assert_eq!(Duration::from_millis(10), timeout),
+            _ => panic!("unexpected error"),
+        }
+    }
+
+    #[test]
+    fn exceeded_limits_cant_be_caught() {
+        let limits = Limits {
+            max_steps: Some(100),
+            max_call_depth: Some(20),
+            ..Limits::default()
+        };
+
+        let error = run_with_limits(
+            "fun f() { f(); }\nvar m; try { f(); } catch (e) { m = e.message; }",
+            limits,
+        );
+        assert!(matches!(error, RuntimeError::CallDepthExceeded(20, 1)));
+
+        let error = run_with_limits(
+            "try { while (true) {} } finally { while (true) {} }",
+            limits,
+        );
+        assert!(matches!(error, RuntimeError::StepLimitExceeded(100, 1)));
+    }
+
+    #[test]
+    fn limit_memory() {
+        let limits = Limits {
+            max_memory: Some(10_000),
+            ..Limits::default()
+        };
+
+        let error = run_with_limits("var s = \"ab\";\nwhile (true) s = s + s;", limits);
+        assert!(matches!(error, RuntimeError::OutOfMemory(10_000, 2)));
+
+        let error = run_with_limits("var s = \"ab\".repeat(1000000);", limits);
+        assert!(matches!(error, RuntimeError::OutOfMemory(10_000, 1)));
+
+        // strings created by natives count as well
+        for code in [
+            "var s = \"ab\".repeat(3000);\nvar t = s.upper();",
+            "var s = \"ab\".repeat(3000);\nvar t = str(s);",
+            "var s = \"ab\".repeat(3000);\nvar t = s.substring(0, 6000);",
+        ] {
+            let error = run_with_limits(code, limits);
+            assert!(matches!(error, RuntimeError::OutOfMemory(10_000, 2)));
+        }
+
+        let error = run_with_limits("var l = [];\nfor (;;) l.push(l);", limits);
+        assert!(matches!(error, RuntimeError::OutOfMemory(10_000, 2)));
+
+        let error = run_with_limits(
+            "fun f() { var a = \"ab\".repeat(1000); f(); }\nf();",
+            limits,
+        );
+        assert!(matches!(error, RuntimeError::OutOfMemory(10_000, 1)));
+    }
+
+    #[test]
+    fn count_memory_usage() {
+        let interpreter = run("var s = \"ab\" + \"cd\"; var l = range(0, 10); var m = {1: 2};");
+        let usage = interpreter.memory_usage();
+
+        assert_eq!(4, usage.strings);
+        assert_eq!(10 * size_of::<Value>(), usage.lists);
+        assert!(usage.maps > 0);
+        assert!(usage.environments > 0);
+        assert_eq!(
+            usage.strings + usage.lists + usage.maps + usage.environments,
+            usage.total()
+        );
+    }
+
+    #[test]
+    fn release_freed_memory() {
+        let limits = Limits {
+            max_memory: Some(100_000),
+            ..Limits::default()
+        };
+        let interpreter = run_with_limits_ok(
+            r"
+            fun id(x) { var y = x; return y; }
+            var l = [];
+            var m = {};
+            var i = 0;
+            while (i < 10000) {
+                var j = id(i);
+                l.push(j);
+                l.pop();
+                m[j] = j;
+                m.remove(j);
+                i = i + 1;
+            }",
+            limits,
+        );
+        let usage = interpreter.memory_usage();
+
+        assert_eq!(0, usage.lists);
+        assert_eq!(0, usage.maps);
+
+        // environments captured by closures are kept
+        let interpreter = run("fun f() { var x; return fun () => x; }\nvar g = f();");
+        assert!(interpreter.memory_usage().environments > size_of::<Environment>());
+    }
+
+    #[test]
+    fn eval_expr_with_bindings() {
+        let mut interpreter = run("var tax = 0.5; var price = 1;");
+        let bindings = [("price", Value::Number(20.0)), ("qty", Value::Number(6.0))];
+
+        assert_eq!(
+            Value::Bool(true),
+            interpreter
+                .eval_expr("price * qty * (1 + tax) > 100", &bindings)
+                .unwrap()
+        );
+        // the bindings are gone after the evaluation
+        assert_eq!(Value::Number(1.0), get(&interpreter, "price"));
+
+        assert!(matches!(
+            interpreter.eval_expr("price;", &bindings),
+            Err(LoxError::Parse(_))
+        ));
+        assert!(matches!(
+            interpreter.eval_expr("print price", &bindings),
+            Err(LoxError::Parse(_))
+        ));
+        assert!(matches!(
+            interpreter.eval_expr("price * missing", &bindings),
+            Err(LoxError::Runtime(RuntimeError::UndefinedVariable(_)))
+        ));
+    }
+
+    #[test]
+    fn call_function_from_host() {
+        let mut interpreter = run(r#"
+            var calls = 0;
+            fun handler(request) {
+                calls = calls + 1;
+                if (request == nil) throw Error("no request");
+                return "handled " + request;
+            }
+            class Point { init(x) { this.x = x; } }"#);
+        let handler = interpreter.get_global("handler").unwrap();
+
+        for request in ["a", "b"] {
+            assert_eq!(
+                Value::String(format!("handled {request}")),
+                interpreter.call(&handler, &[Value::from(request)]).unwrap()
+            );
+        }
+        assert_eq!(Some(Value::Number(2.0)), interpreter.get_global("calls"));
+
+        let point = interpreter.get_global("Point").unwrap();
+        let point = interpreter.call(&point, &[Value::Number(1.0)]).unwrap();
+        assert_eq!("Point instance", point.to_string());
+
+        match interpreter.call(&handler, &[Value::Nil]) {
+            Err(RuntimeError::Throw(_, 5)) => {}
+            _ => panic!("unexpected result"),
+        }
+        assert!(matches!(
+            interpreter.call(&handler, &[]),
+            Err(RuntimeError::ArityMismatch(1, 0, 0))
+        ));
+        assert!(matches!(
+            interpreter.call(&Value::Nil, &[]),
+            Err(RuntimeError::ValueNotCallable(Value::Nil, 0))
+        ));
+        assert_eq!(None, interpreter.get_global("missing"));
+        assert!(interpreter.get_global("clock").is_some());
+    }
+
+    #[test]
+    fn cancel_run() {
+        let statements = Parser::new()
+            .parse(Scanner::scan("fun f() {}\nf();").unwrap())
+            .unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.cancel_handle().cancel();
+
+        match interpreter.interpret(&statements) {
+            Err(RuntimeError::Cancelled(2)) => {}
+            _ => panic!("unexpected result"),
+        }
+        // only the cancelled run is stopped
+        assert!(interpreter.interpret(&statements).is_ok());
+    }
+
+    #[test]
+    fn cancel_run_in_try() {
+        let statements = Parser::new()
+            .parse(
+                Scanner::scan("var n = 0;\ntry { while (true) n = n + 1; } catch (e) {}").unwrap(),
+            )
+            .unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.cancel_handle().cancel();
+
+        match interpreter.interpret(&statements) {
+            Err(RuntimeError::Cancelled(2)) => {}
+            _ => panic!("unexpected result"),
+        }
+        // the cancellation isn't caught and doesn't carry over to the next run
+        let statements = Parser::new()
+            .parse(Scanner::scan("for (var i = 0; i < 10; i = i + 1) {}").unwrap())
+            .unwrap();
+        assert!(interpreter.interpret(&statements).is_ok());
+    }
+
+    #[test]
+    fn print_to_output() {
+        let output = SharedOutput::default();
+        let statements = Parser::new()
+            .parse(Scanner::scan("print 1 + 2; print \"a\"; print nil;").unwrap())
+            .unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output(output.clone());
+        interpreter.interpret(&statements).unwrap();
+
+        assert_eq!("3\na\nnil\n", String::from_utf8(output.0.take()).unwrap());
+    }
+
+    // An output that can still be read after it was handed to the interpreter
+    #[derive(Clone, Default)]
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn run(code: &str) -> Interpreter {
+        let statements = Parser::new().parse(Scanner::scan(code).unwrap()).unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.interpret(&statements).unwrap();
+
+        interpreter
+    }
+
+    fn run_file(path: &Path) -> Result<Interpreter, RuntimeError> {
+        let code = fs::read_to_string(path).unwrap();
+        let statements = Parser::new().parse(Scanner::scan(&code).unwrap()).unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_script_path(path);
+
+        interpreter.interpret(&statements).map(|_| interpreter)
+    }
+
+    // Creates an empty directory with a 'lib' subdirectory for the modules of a test
+    fn module_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rlox-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("lib")).unwrap();
+
+        dir
+    }
+
+    fn run_with_error(code: &str) -> RuntimeError {
+        let statements = Parser::new().parse(Scanner::scan(code).unwrap()).unwrap();
+        Interpreter::new().interpret(&statements).unwrap_err()
+    }
+
+    fn run_with_limits(code: &str, limits: Limits) -> RuntimeError {
+        let statements = Parser::new().parse(Scanner::scan(code).unwrap()).unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(limits);
+
+        interpreter.interpret(&statements).unwrap_err()
+    }
+
+    fn run_with_limits_ok(code: &str, limits: Limits) -> Interpreter {
+        let statements = Parser::new().parse(Scanner::scan(code).unwrap()).unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(limits);
+        interpreter.interpret(&statements).unwrap();
+
+        interpreter
+    }
+
+    fn get(interpreter: &Interpreter, name: &str) -> Value {
+        interpreter
+            .environment
+            .borrow()
+            .get(&token(TokenType::Identifier(name.to_string())))
+            .unwrap()
+    }
+
     fn token(token_type: TokenType) -> Token {
         Token::new(token_type, 1)
     }
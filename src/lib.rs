@@ -0,0 +1,51 @@
+#![warn(clippy::all, clippy::nursery, clippy::pedantic)]
+
+mod capability;
+mod clock;
+mod conversion;
+mod environment;
+mod error;
+mod expr;
+mod interpreter;
+mod limits;
+mod literal;
+mod lox;
+mod lox_callable;
+mod lox_class;
+mod lox_function;
+mod lox_instance;
+mod lox_iterator;
+mod lox_map;
+mod lox_module;
+mod native;
+mod parser;
+mod random;
+mod scanner;
+mod stmt;
+mod time_source;
+mod token;
+mod token_type;
+mod value;
+
+pub use crate::capability::Capability;
+pub use crate::conversion::{ConversionError, TypedFunction};
+pub use crate::error::{LoxError, ParseError, RuntimeError, ScanError};
+pub use crate::expr::Expr;
+pub use crate::interpreter::Interpreter;
+pub use crate::limits::{
+    CancelHandle, Limits, MemoryUsage, DEFAULT_MAX_CALL_DEPTH, DEFAULT_MAX_STACK,
+};
+pub use crate::literal::Literal;
+pub use crate::lox::Lox;
+pub use crate::lox_callable::LoxCallable;
+pub use crate::lox_class::LoxClass;
+pub use crate::lox_instance::LoxInstance;
+pub use crate::lox_map::{LoxMap, MapKey};
+pub use crate::lox_module::LoxModule;
+pub use crate::parser::Parser;
+pub use crate::scanner::Scanner;
+pub use crate::stmt::Stmt;
+pub use crate::time_source::{FixedTimeSource, SystemTimeSource, TimeSource};
+pub use crate::token::Token;
+pub use crate::token_type::TokenType;
+pub use crate::value::Value;
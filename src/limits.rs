@@ -0,0 +1,159 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::environment::Environment;
+use crate::lox_map::MapKey;
+use crate::value::Value;
+
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
+// Half of the default stack of threads spawned by std, leaving the rest to the embedder
+pub const DEFAULT_MAX_STACK: usize = 1024 * 1024;
+
+// Bounds on the resources a program may use, for running untrusted programs. None means
+// unlimited. Steps, time and memory are counted from the start of each run, so max_memory
+// doesn't cover what earlier runs allocated, e.g. values that are still held by globals.
+//
+// Every Lox call is a number of nested Rust calls, which take a few kilobytes of native stack in
+// release builds and tens of kilobytes in debug builds, as is evaluating deeply nested
+// expressions. Deep recursion is stopped by max_stack before it overflows the native stack, so the thread running the interpreter needs a stack of
+// at least max_stack plus what the embedder uses. The defaults are safe on threads with 2 MB of
+// stack, the default of threads spawned by std. Without max_stack, max_call_depth alone doesn't
+// prevent an overflow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    // the number of statements that may be executed
+    pub max_steps: Option<u64>,
+    // the number of nested calls, including calls of natives
+    pub max_call_depth: Option<usize>,
+    // the number of bytes of native stack that nested calls and expressions may use, from the
+    // start of the run
+    pub max_stack: Option<usize>,
+    // the wall-clock time a run may take
+    pub timeout: Option<Duration>,
+    // the approximate number of bytes that may be allocated for strings, lists, maps and
+    // environments
+    pub max_memory: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_steps: None,
+            max_call_depth: Some(DEFAULT_MAX_CALL_DEPTH),
+            max_stack: Some(DEFAULT_MAX_STACK),
+            timeout: None,
+            max_memory: None,
+        }
+    }
+}
+
+// Stops the program that is running, or the next one if none is, from any thread
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+}
+
+// The approximate number of bytes allocated by the current run, per kind of value, not including
+// what earlier runs allocated. Environments are released when they are freed, and elements when
+// they are removed from lists and maps. Other memory that is freed again, e.g. of strings or of
+// lists that are no longer used, is still counted, so this is an upper bound of the memory the
+// run added.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub strings: usize,
+    pub lists: usize,
+    pub maps: usize,
+    pub environments: usize,
+}
+
+impl MemoryUsage {
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.strings
+            .saturating_add(self.lists)
+            .saturating_add(self.maps)
+            .saturating_add(self.environments)
+    }
+
+    // Sizes saturate, as the sizes of allocations the program asks for can be arbitrarily large
+    pub(crate) const fn add(&mut self, allocation: Allocation) {
+        let counter = self.counter(allocation);
+        *counter = counter.saturating_add(allocation.size());
+    }
+
+    // Releases an allocation that was freed, e.g. the elements removed from a list
+    pub(crate) const fn release(&mut self, allocation: Allocation) {
+        let counter = self.counter(allocation);
+        *counter = counter.saturating_sub(allocation.size());
+    }
+
+    const fn counter(&mut self, allocation: Allocation) -> &mut usize {
+        match allocation {
+            Allocation::Environment | Allocation::Variable(_) => &mut self.environments,
+            Allocation::List(_) => &mut self.lists,
+            Allocation::Map(_) => &mut self.maps,
+            Allocation::String(_) => &mut self.strings,
+        }
+    }
+}
+
+// An allocation, with the number of bytes of strings and variable names, and the number of
+// elements of lists and entries of maps
+#[derive(Clone, Copy, Debug)]
+pub enum Allocation {
+    Environment,
+    List(usize),
+    Map(usize),
+    String(usize),
+    Variable(usize),
+}
+
+impl Allocation {
+    const fn size(self) -> usize {
+        match self {
+            Self::Environment => size_of::<Environment>(),
+            Self::List(len) => len.saturating_mul(size_of::<Value>()),
+            Self::Map(len) => len.saturating_mul(size_of::<(MapKey, Value)>()),
+            Self::String(len) => len,
+            Self::Variable(name_len) => name_len.saturating_add(size_of::<(String, Value)>()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saturate_memory_usage() {
+        let mut usage = MemoryUsage::default();
+        usage.add(Allocation::List(usize::MAX / 2));
+        usage.add(Allocation::String(usize::MAX));
+        usage.add(Allocation::Map(usize::MAX));
+
+        assert_eq!(usize::MAX, usage.lists);
+        assert_eq!(usize::MAX, usage.total());
+    }
+}
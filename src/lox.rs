@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::LoxError;
+use crate::interpreter::Interpreter;
+use crate::parser::Parser;
+use crate::scanner::Scanner;
+use crate::value::Value;
+
+// Runs Lox programs, the state of the programs is kept between runs
+#[derive(Default)]
+pub struct Lox {
+    interpreter: Interpreter,
+}
+
+impl Lox {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub const fn interpreter(&self) -> &Interpreter {
+        &self.interpreter
+    }
+
+    pub const fn interpreter_mut(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+
+    /// Runs the program and returns the value of its last statement if that is an expression
+    /// statement, and nil otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the program can't be scanned or parsed, or if it raises an uncaught error.
+    pub fn run(&mut self, source: &str) -> Result<Value, LoxError> {
+        let tokens = Scanner::scan(source)?;
+        let statements = Parser::new().parse(tokens)?;
+
+        Ok(self.interpreter.interpret(&statements)?)
+    }
+
+    /// Runs the program in the file, imports are resolved relative to the file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read, and for the same reasons as [`Lox::run`].
+    pub fn run_file(&mut self, path: &Path) -> Result<Value, LoxError> {
+        let source = fs::read_to_string(path)?;
+        self.interpreter.set_script_path(path);
+
+        self.run(&source)
+    }
+}
@@ -1,11 +1,29 @@
+use std::any::Any;
 use std::fmt;
 
+use crate::error::RuntimeError;
 use crate::interpreter::Interpreter;
+use crate::lox_function::LoxFunction;
 use crate::value::Value;
 
 pub trait LoxCallable: CallableClone {
     fn arity(&self) -> usize;
-    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Value;
+    /// Calls the callable with arguments matching its arity. The line of the call is used for
+    /// error messages.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the errors raised by the callable.
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        line: usize,
+    ) -> Result<Value, RuntimeError>;
+    fn name(&self) -> &str;
+
+    // Needed to find out whether two callables are of the same type, see the PartialEq impl below
+    fn as_any(&self) -> &dyn Any;
 }
 
 // workaround based on https://stackoverflow.com/questions/30353462/how-to-clone-a-struct-storing-a-boxed-trait-object/30353928
@@ -30,19 +48,76 @@ impl Clone for Box<dyn LoxCallable> {
 
 impl fmt::Debug for Box<dyn LoxCallable> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "<LoxCallable>")
+        write!(f, "{self}")
+    }
+}
+
+impl fmt::Display for Box<dyn LoxCallable> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.as_any().is::<LoxFunction>() {
+            write!(f, "<fn {}>", self.name())
+        } else {
+            write!(f, "<native fn>")
+        }
     }
 }
 
+// User functions are equal if they originate from the same declaration, native functions if they
+// have the same name.
 impl PartialEq for Box<dyn LoxCallable> {
     fn eq(&self, other: &Self) -> bool {
-        self == other
+        match (
+            self.as_any().downcast_ref::<LoxFunction>(),
+            other.as_any().downcast_ref::<LoxFunction>(),
+        ) {
+            (Some(function), Some(other_function)) => function == other_function,
+            (None, None) => self.name() == other.name(),
+            _ => false,
+        }
     }
 }
 
 // workaround for bug https://github.com/rust-lang/rust/issues/31740
 impl PartialEq<&Self> for Box<dyn LoxCallable> {
     fn eq(&self, other: &&Self) -> bool {
-        self == other
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Clock;
+    use crate::environment::Environment;
+    use crate::token::Token;
+    use crate::token_type::TokenType;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn display() {
+        let function: Box<dyn LoxCallable> = Box::new(lox_function("test", 1));
+        assert_eq!("<fn test>", format!("{function}"));
+
+        let native: Box<dyn LoxCallable> = Box::new(Clock::new());
+        assert_eq!("<native fn>", format!("{native}"));
+    }
+
+    #[test]
+    fn equality() {
+        let function: Box<dyn LoxCallable> = Box::new(lox_function("test", 1));
+        let same_function: Box<dyn LoxCallable> = Box::new(lox_function("test", 1));
+        let other_function: Box<dyn LoxCallable> = Box::new(lox_function("test", 2));
+        let native: Box<dyn LoxCallable> = Box::new(Clock::new());
+
+        assert!(function == same_function);
+        assert!(function != other_function);
+        assert!(function != native);
+        assert!(native == native.clone());
+    }
+
+    fn lox_function(name: &str, line: usize) -> LoxFunction {
+        let name = Token::new(TokenType::Identifier(name.to_string()), line);
+        LoxFunction::new(&name, &[], &[], Rc::new(RefCell::new(Environment::new())))
     }
 }
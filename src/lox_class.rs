@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::lox_callable::LoxCallable;
+use crate::lox_function::LoxFunction;
+
+pub struct LoxClass {
+    name: String,
+    methods: HashMap<String, LoxFunction>,
+}
+
+impl LoxClass {
+    #[must_use]
+    pub fn new(name: &str, methods: HashMap<String, LoxFunction>) -> Self {
+        Self {
+            name: name.to_string(),
+            methods,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn find_method(&self, name: &str) -> Option<&LoxFunction> {
+        self.methods.get(name)
+    }
+
+    // The arity of a class is the arity of its initializer, if it has one
+    pub fn arity(&self) -> usize {
+        self.find_method("init").map_or(0, LoxCallable::arity)
+    }
+}
+
+// Classes are only equal to themselves
+impl PartialEq for LoxClass {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for LoxClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<class {}>", self.name)
+    }
+}
+
+impl fmt::Display for LoxClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
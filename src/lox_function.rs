@@ -1,45 +1,117 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use crate::environment::Environment;
 use crate::error::RuntimeError;
 use crate::interpreter::Interpreter;
 use crate::lox_callable::LoxCallable;
 use crate::stmt::Stmt;
 use crate::token::Token;
+use crate::token_type::TokenType;
 use crate::value::Value;
 
 #[derive(Clone)]
 pub struct LoxFunction {
-    name: Token,
+    // None for anonymous functions
+    name: Option<Token>,
     params: Vec<Token>,
     body: Vec<Stmt>,
+    closure: Rc<RefCell<Environment>>,
+    // initializers always return 'this'
+    is_initializer: bool,
 }
 
 impl LoxFunction {
-    pub fn new(name: &Token, params: &[Token], body: &[Stmt]) -> Self {
+    pub fn new(
+        name: &Token,
+        params: &[Token],
+        body: &[Stmt],
+        closure: Rc<RefCell<Environment>>,
+    ) -> Self {
+        Self {
+            name: Some(name.clone()),
+            params: params.to_owned(),
+            body: body.to_owned(),
+            closure,
+            is_initializer: false,
+        }
+    }
+
+    pub fn new_method(
+        name: &Token,
+        params: &[Token],
+        body: &[Stmt],
+        closure: Rc<RefCell<Environment>>,
+    ) -> Self {
+        Self {
+            is_initializer: name.lexeme == "init",
+            ..Self::new(name, params, body, closure)
+        }
+    }
+
+    pub fn new_lambda(params: &[Token], body: &[Stmt], closure: Rc<RefCell<Environment>>) -> Self {
         Self {
-            name: name.clone(),
+            name: None,
             params: params.to_owned(),
             body: body.to_owned(),
+            closure,
+            is_initializer: false,
+        }
+    }
+
+    // Returns a copy of the method in which 'this' refers to the given instance
+    pub fn bind(&self, instance: Value) -> Self {
+        let mut env = Environment::new_with_parent(Rc::clone(&self.closure));
+        env.define("this".to_string(), instance);
+
+        Self {
+            closure: Rc::new(RefCell::new(env)),
+            ..self.clone()
         }
     }
 }
 
+// The closure is ignored, functions are equal if they originate from the same declaration.
+impl PartialEq for LoxFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.params == other.params && self.body == other.body
+    }
+}
+
 impl LoxCallable for LoxFunction {
     fn arity(&self) -> usize {
         self.params.len()
     }
 
-    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Value {
-        let mut env = Environment::new_with_parent(interpreter.environment.clone());
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        line: usize,
+    ) -> Result<Value, RuntimeError> {
+        let mut env = Environment::new_with_parent(Rc::clone(&self.closure));
 
-        for (i, param) in self.params.iter().enumerate() {
-            env.define(param.lexeme.clone(), arguments[i].clone());
+        for (param, argument) in self.params.iter().zip(arguments) {
+            env.define(param.lexeme.clone(), argument);
         }
 
-        let result = interpreter.execute_block(&self.body, &env);
-
-        match result {
-            Err(RuntimeError::Return(value)) => value,
-            _ => Value::Nil,
+        match interpreter.execute_block(&self.body, env) {
+            Ok(()) | Err(RuntimeError::Return(_)) if self.is_initializer => self
+                .closure
+                .borrow()
+                .get(&Token::new(TokenType::This, line)),
+            Ok(()) => Ok(Value::Nil),
+            Err(RuntimeError::Return(value)) => Ok(value),
+            Err(e) => Err(e),
         }
     }
+
+    fn name(&self) -> &str {
+        self.name.as_ref().map_or("lambda", |name| &name.lexeme)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
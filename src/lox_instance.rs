@@ -0,0 +1,74 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::error::RuntimeError;
+use crate::lox_class::LoxClass;
+use crate::token::Token;
+use crate::value::Value;
+
+#[derive(Debug)]
+pub struct LoxInstance {
+    class: Rc<LoxClass>,
+    fields: HashMap<String, Value>,
+}
+
+impl LoxInstance {
+    #[must_use]
+    pub fn new(class: Rc<LoxClass>) -> Self {
+        Self {
+            class,
+            fields: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn class(&self) -> &Rc<LoxClass> {
+        &self.class
+    }
+
+    /// Looks up a field or, if there is no field with the given name, a method. Methods are bound
+    /// to the instance, which is the reason this function takes the instance as a parameter.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is neither a field nor a method with the given name.
+    pub fn get(instance: &Rc<RefCell<Self>>, name: &Token) -> Result<Value, RuntimeError> {
+        let this = instance.borrow();
+
+        if let Some(value) = this.fields.get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        this.class.find_method(&name.lexeme).map_or_else(
+            || Err(RuntimeError::UndefinedProperty(name.clone())),
+            |method| {
+                let bound_method = method.bind(Value::Instance(Rc::clone(instance)));
+                Ok(Value::Function(Box::new(bound_method)))
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn get_field(&self, name: &str) -> Option<Value> {
+        self.fields.get(name).cloned()
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        self.fields.insert(name.to_string(), value);
+    }
+}
+
+// Instances are only equal to themselves
+impl PartialEq for LoxInstance {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Display for LoxInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} instance", self.class.name())
+    }
+}
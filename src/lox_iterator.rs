@@ -0,0 +1,101 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::lox_instance::LoxInstance;
+use crate::token::Token;
+use crate::token_type::TokenType;
+use crate::value::Value;
+
+// The state of a 'for (var x in iterable)' loop
+pub enum LoxIterator {
+    // lists are read by index, so elements pushed while looping are visited too
+    List(Rc<RefCell<Vec<Value>>>, usize),
+    // map keys and string characters are collected when the loop starts
+    Values(Vec<Value>, usize),
+    // instances implement the protocol with 'has_next()' and 'next()' methods
+    Instance(Rc<RefCell<LoxInstance>>),
+}
+
+impl LoxIterator {
+    // Creates an iterator over the given value. Instances of a class with an 'iterator()' method
+    // are iterated over the value it returns, other instances are iterated over directly.
+    pub fn new(
+        interpreter: &mut Interpreter,
+        value: Value,
+        line: usize,
+    ) -> Result<Self, RuntimeError> {
+        match value {
+            Value::List(list) => Ok(Self::List(list, 0)),
+            Value::Map(map) => {
+                let keys: Vec<Value> = map.borrow().iter().map(|(key, _)| key.to_value()).collect();
+                Ok(Self::Values(keys, 0))
+            }
+            Value::String(string) => {
+                let chars: Vec<Value> = string
+                    .chars()
+                    .map(|c| Value::String(c.to_string()))
+                    .collect();
+                Ok(Self::Values(chars, 0))
+            }
+            Value::Instance(instance) => {
+                let has_iterator = instance.borrow().class().find_method("iterator").is_some();
+
+                if has_iterator {
+                    match call_method(interpreter, &instance, "iterator", line)? {
+                        Value::Instance(iterator) => Ok(Self::Instance(iterator)),
+                        value => Err(RuntimeError::ValueNotIterable(value, line)),
+                    }
+                } else {
+                    Ok(Self::Instance(instance))
+                }
+            }
+            _ => Err(RuntimeError::ValueNotIterable(value, line)),
+        }
+    }
+
+    pub fn next(
+        &mut self,
+        interpreter: &mut Interpreter,
+        line: usize,
+    ) -> Result<Option<Value>, RuntimeError> {
+        match self {
+            Self::List(list, index) => {
+                let next = list.borrow().get(*index).cloned();
+                *index += 1;
+                Ok(next)
+            }
+            Self::Values(values, index) => {
+                let next = values.get(*index).cloned();
+                *index += 1;
+                Ok(next)
+            }
+            Self::Instance(instance) => {
+                if call_method(interpreter, instance, "has_next", line)?.is_truthy() {
+                    call_method(interpreter, instance, "next", line).map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+}
+
+// Calls the method with the given name and no arguments on the instance
+fn call_method(
+    interpreter: &mut Interpreter,
+    instance: &Rc<RefCell<LoxInstance>>,
+    name: &str,
+    line: usize,
+) -> Result<Value, RuntimeError> {
+    let token = Token::new(TokenType::Identifier(name.to_string()), line);
+
+    match LoxInstance::get(instance, &token)? {
+        Value::Function(method) if method.arity() == 0 => {
+            method.call(interpreter, Vec::new(), line)
+        }
+        Value::Function(method) => Err(RuntimeError::ArityMismatch(method.arity(), 0, line)),
+        value => Err(RuntimeError::ValueNotCallable(value, line)),
+    }
+}
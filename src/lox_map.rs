@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use crate::value::Value;
+
+// The values that can be used as keys of a map
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MapKey {
+    Bool(bool),
+    Nil,
+    // the bits of the number, as f64 implements neither Eq nor Hash
+    Number(u64),
+    String(String),
+}
+
+impl MapKey {
+    #[must_use]
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(bool) => Some(Self::Bool(*bool)),
+            Value::Nil => Some(Self::Nil),
+            // 0.0 and -0.0 are equal, hence they should be the same key
+            Value::Number(number) if *number == 0.0 => Some(Self::Number(0.0_f64.to_bits())),
+            Value::Number(number) => Some(Self::Number(number.to_bits())),
+            Value::String(string) => Some(Self::String(string.clone())),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        match self {
+            Self::Bool(bool) => Value::Bool(*bool),
+            Self::Nil => Value::Nil,
+            Self::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            Self::String(string) => Value::String(string.clone()),
+        }
+    }
+}
+
+// A map that keeps its entries in insertion order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoxMap {
+    entries: Vec<(MapKey, Value)>,
+    indices: HashMap<MapKey, usize>,
+}
+
+impl LoxMap {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &MapKey) -> Option<&Value> {
+        self.indices.get(key).map(|i| &self.entries[*i].1)
+    }
+
+    #[must_use]
+    pub fn contains_key(&self, key: &MapKey) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    // Updating the value of an existing key keeps the position of the key
+    pub fn insert(&mut self, key: MapKey, value: Value) {
+        if let Some(i) = self.indices.get(&key) {
+            self.entries[*i].1 = value;
+        } else {
+            self.indices.insert(key.clone(), self.entries.len());
+            self.entries.push((key, value));
+        }
+    }
+
+    pub fn remove(&mut self, key: &MapKey) -> Option<Value> {
+        let i = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(i);
+
+        for index in self.indices.values_mut() {
+            if *index > i {
+                *index -= 1;
+            }
+        }
+
+        Some(value)
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&MapKey, &Value)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn insert_and_get() {
+        let mut map = LoxMap::new();
+        map.insert(key("a"), Value::Number(1.0));
+        map.insert(key("a"), Value::Number(2.0));
+
+        assert_eq!(Some(&Value::Number(2.0)), map.get(&key("a")));
+        assert_eq!(None, map.get(&key("b")));
+        assert_eq!(1, map.len());
+    }
+
+    #[test]
+    fn keep_insertion_order() {
+        let mut map = LoxMap::new();
+        for name in ["c", "a", "b"] {
+            map.insert(key(name), Value::Nil);
+        }
+        map.remove(&key("a"));
+        map.insert(key("a"), Value::Nil);
+        map.insert(key("c"), Value::Bool(true));
+
+        let keys: Vec<&MapKey> = map.iter().map(|(key, _)| key).collect();
+        assert_eq!(vec![&key("c"), &key("b"), &key("a")], keys);
+        assert_eq!(Some(&Value::Bool(true)), map.get(&key("c")));
+    }
+
+    #[test]
+    fn remove() {
+        let mut map = LoxMap::new();
+        map.insert(key("a"), Value::Number(1.0));
+        map.insert(key("b"), Value::Number(2.0));
+
+        assert_eq!(Some(Value::Number(1.0)), map.remove(&key("a")));
+        assert_eq!(None, map.remove(&key("a")));
+        assert_eq!(Some(&Value::Number(2.0)), map.get(&key("b")));
+    }
+
+    #[test]
+    fn keys_from_values() {
+        assert_eq!(
+            MapKey::from_value(&Value::Number(0.0)),
+            MapKey::from_value(&Value::Number(-0.0))
+        );
+        assert_eq!(
+            Some(Value::Number(1.5)),
+            MapKey::from_value(&Value::Number(1.5)).map(|key| key.to_value())
+        );
+        assert_eq!(Some(MapKey::Nil), MapKey::from_value(&Value::Nil));
+        assert_eq!(None, MapKey::from_value(&Value::List(Rc::default())));
+    }
+
+    fn key(name: &str) -> MapKey {
+        MapKey::String(name.to_string())
+    }
+}
@@ -0,0 +1,150 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::environment::Environment;
+use crate::error::RuntimeError;
+use crate::parser::Parser;
+use crate::scanner::Scanner;
+use crate::stmt::Stmt;
+use crate::token::Token;
+use crate::value::Value;
+
+// The namespace of an imported file
+pub struct LoxModule {
+    name: String,
+    environment: Rc<RefCell<Environment>>,
+    // the names declared with 'export', all other names are private to the module
+    exports: HashSet<String>,
+}
+
+impl LoxModule {
+    pub fn new(name: &str, environment: Rc<RefCell<Environment>>, statements: &[Stmt]) -> Self {
+        Self {
+            name: name.to_string(),
+            environment,
+            exports: exports(statements),
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Looks up a name declared by the module itself, globals like `clock` aren't part of a
+    /// module.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the module doesn't have or doesn't export the name.
+    pub fn get(&self, name: &Token) -> Result<Value, RuntimeError> {
+        let is_exported = self.exports.contains(&name.lexeme);
+
+        self.environment
+            .borrow()
+            .get_own(&name.lexeme)
+            .filter(|_| is_exported)
+            .ok_or_else(|| RuntimeError::NotExported(self.name.clone(), name.clone()))
+    }
+}
+
+// Returns the name of the module in the given file, which is the file name without extension
+pub fn name(path: &Path) -> String {
+    path.file_stem()
+        .map_or_else(String::new, |stem| stem.to_string_lossy().to_string())
+}
+
+// Returns the names a module with the given statements exports
+pub fn exports(statements: &[Stmt]) -> HashSet<String> {
+    statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Stmt::Export(_, declaration) => match declaration.as_ref() {
+                Stmt::Class(name, _) | Stmt::Function(name, _, _) | Stmt::Var(name, _) => {
+                    Some(name.lexeme.clone())
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+// Returns the paths and names of all 'from ... import' statements, also of those in functions and
+// branches that might never run
+pub fn imports_from(statements: &[Stmt]) -> Vec<(&str, &[Token])> {
+    let mut imports = Vec::new();
+    collect_imports_from(statements, &mut imports);
+
+    imports
+}
+
+fn collect_imports_from<'a>(statements: &'a [Stmt], imports: &mut Vec<(&'a str, &'a [Token])>) {
+    for statement in statements {
+        match statement {
+            Stmt::ImportFrom(_, path, names) => imports.push((path, names)),
+            Stmt::Block(statements)
+            | Stmt::Class(_, statements)
+            | Stmt::Function(_, _, statements) => collect_imports_from(statements, imports),
+            Stmt::Export(_, statement)
+            | Stmt::ForIn(_, _, statement)
+            | Stmt::While(_, _, statement, _) => {
+                collect_imports_from(std::slice::from_ref(statement), imports);
+            }
+            Stmt::If(_, then_branch, else_branch) => {
+                collect_imports_from(std::slice::from_ref(then_branch), imports);
+                if let Some(else_branch) = else_branch {
+                    collect_imports_from(std::slice::from_ref(else_branch), imports);
+                }
+            }
+            Stmt::Try(body, catch, finally) => {
+                collect_imports_from(body, imports);
+                if let Some((_, handler)) = catch {
+                    collect_imports_from(handler, imports);
+                }
+                if let Some(finally) = finally {
+                    collect_imports_from(finally, imports);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Reads and parses the file of a module. Fails with a description of the problem if the file
+// can't be read or isn't a valid program.
+pub fn load(path: &Path) -> Result<Vec<Stmt>, String> {
+    let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let tokens = Scanner::scan(&source).map_err(|e| e.to_string())?;
+
+    Parser::new().parse(tokens).map_err(|errors| {
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(" ")
+    })
+}
+
+// Modules are only equal to themselves
+impl PartialEq for LoxModule {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for LoxModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<module {}>", self.name)
+    }
+}
+
+impl fmt::Display for LoxModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<module {}>", self.name)
+    }
+}
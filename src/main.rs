@@ -1,90 +1,158 @@
 #![warn(clippy::all, clippy::nursery, clippy::pedantic)]
 
-mod clock;
-mod environment;
-mod error;
-mod expr;
-mod interpreter;
-mod literal;
-mod lox_callable;
-mod lox_function;
-mod parser;
-mod scanner;
-mod stmt;
-mod token;
-mod token_type;
-mod value;
-
 use std::env;
-use std::fs;
 use std::io;
-use std::io::BufRead;
+use std::path::Path;
+use std::thread;
+
+use rlox::{Capability, Limits, Lox, LoxError};
+
+struct Options {
+    // sandboxed runs can restrict the natives that interact with the outside world
+    capabilities: Vec<Capability>,
+    // an alias for leaving out fs, which wins over --allow regardless of the order of the flags
+    no_fs: bool,
+    // a frozen clock and seeded random numbers make runs reproducible, e.g. for snapshot tests
+    deterministic: bool,
+    seed: Option<u64>,
+    // the arguments after the script, available to the script through 'args()'
+    script_args: Vec<String>,
+}
 
-use crate::interpreter::Interpreter;
-use crate::parser::Parser;
-use crate::scanner::Scanner;
+// Every Lox call takes a number of nested Rust calls, the default stack is too small for the
+// default maximum call depth
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+// The stack scripts may use, the rest is left to the code around the interpreter
+const MAX_STACK: usize = STACK_SIZE - 1024 * 1024;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    match args.len() {
-        1 => run_prompt(),
-        2 => run_file(&args[1]),
-        _ => {
-            println!("Usage: rlox [script]");
-            // exit code from https://www.freebsd.org/cgi/man.cgi?query=sysexits&apropos=0&sektion=0&manpath=FreeBSD+4.3-RELEASE&format=html
-            std::process::exit(64);
-        }
+    let interpreter = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("the interpreter thread can be started");
+
+    // errors are reported by the thread itself, a panic has already been printed
+    if interpreter.join().is_err() {
+        std::process::exit(70);
     }
 }
 
-fn run_prompt() {
-    for line in io::stdin().lock().lines() {
-        run(&line.unwrap());
+fn run() {
+    let mut args = env::args().skip(1).peekable();
+    let mut options = Options {
+        capabilities: Capability::ALL.to_vec(),
+        no_fs: false,
+        deterministic: false,
+        seed: None,
+        script_args: Vec::new(),
+    };
+
+    // options come before the script, everything after the script is passed to the script
+    while let Some(arg) = args.next_if(|arg| arg.starts_with("--")) {
+        if let Some(names) = arg.strip_prefix("--allow=") {
+            options.capabilities = names
+                .split(',')
+                .filter(|name| !name.is_empty())
+                .map(|name| Capability::from_name(name).unwrap_or_else(|| usage()))
+                .collect();
+        } else if arg == "--deterministic" {
+            options.deterministic = true;
+        } else if arg == "--seed" {
+            let seed = args.next().and_then(|seed| seed.parse().ok());
+            options.seed = Some(seed.unwrap_or_else(|| usage()));
+        } else if arg == "--no-fs" {
+            options.no_fs = true;
+        } else {
+            usage();
+        }
     }
-}
 
-fn run_file(path: &str) {
-    let file_content = fs::read_to_string(path);
+    if options.no_fs {
+        options
+            .capabilities
+            .retain(|&capability| capability != Capability::Fs);
+    }
 
-    match file_content {
-        Ok(source) => run(&source),
-        Err(e) => println!("{}: {}!", path, e),
+    match args.next() {
+        None => run_prompt(&options),
+        Some(path) => {
+            options.script_args = args.collect();
+            run_file(&path, &options);
+        }
     }
 }
 
-fn run(source: &str) {
-    let scan_result = Scanner::scan(source);
+fn usage() -> ! {
+    println!(
+        "Usage: rlox [--allow=capability,...] [--no-fs] [--deterministic] [--seed N] [script [arguments...]]"
+    );
+    let names: Vec<&str> = Capability::ALL.iter().map(|c| c.name()).collect();
+    println!("Capabilities: {}", names.join(", "));
+    println!("--no-fs denies fs, also if it is allowed by --allow");
+    // exit code from https://www.freebsd.org/cgi/man.cgi?query=sysexits&apropos=0&sektion=0&manpath=FreeBSD+4.3-RELEASE&format=html
+    std::process::exit(64);
+}
 
-    if scan_result.is_err() {
-        eprintln!("{}", scan_result.unwrap_err());
-        // code 65: incorrect input data
-        std::process::exit(65);
+fn new_lox(options: &Options) -> Lox {
+    let mut lox = Lox::new();
+    lox.interpreter_mut().set_limits(Limits {
+        max_stack: Some(MAX_STACK),
+        ..Limits::default()
+    });
+    lox.interpreter_mut()
+        .set_capabilities(&options.capabilities);
+    lox.interpreter_mut().set_script_args(&options.script_args);
+
+    if options.deterministic {
+        lox.interpreter_mut()
+            .set_deterministic(options.seed.unwrap_or(0));
+    } else if let Some(seed) = options.seed {
+        lox.interpreter_mut().set_seed(seed);
     }
 
-    let tokens = scan_result.unwrap();
+    lox
+}
 
-    let parse_result = Parser::new().parse(tokens.clone());
+// The state is kept between lines, errors are reported without leaving the prompt
+fn run_prompt(options: &Options) {
+    let mut lox = new_lox(options);
 
-    if let Err(errors) = parse_result {
-        for error in errors {
-            eprintln!("{}", error);
+    // stdin isn't locked while a line runs, as scripts may read from stdin themselves
+    let mut line = String::new();
+    while io::stdin().read_line(&mut line).is_ok_and(|read| read > 0) {
+        if let Err(e) = lox.run(&line) {
+            report(&lox, &e);
         }
-        std::process::exit(65);
+        line.clear();
     }
+}
 
-    let statements = parse_result.unwrap();
-    let interpret_result = Interpreter::new().interpret(statements.clone());
-
-    if interpret_result.is_err() {
-        eprintln!("{}", interpret_result.unwrap_err());
+fn run_file(path: &str, options: &Options) {
+    let mut lox = new_lox(options);
+
+    match lox.run_file(Path::new(path)) {
+        Ok(_) => {}
+        Err(LoxError::Io(e)) => println!("{path}: {e}!"),
+        Err(e) => {
+            report(&lox, &e);
+            // exit codes from https://www.freebsd.org/cgi/man.cgi?query=sysexits&apropos=0&sektion=0&manpath=FreeBSD+4.3-RELEASE&format=html
+            let code = match e {
+                // incorrect input data
+                LoxError::Parse(_) | LoxError::Scan(_) => 65,
+                // internal software error
+                _ => 70,
+            };
+            std::process::exit(code);
+        }
     }
+}
 
-    for token in tokens {
-        println!("{:?}", token);
-    }
+fn report(lox: &Lox, error: &LoxError) {
+    eprintln!("{error}");
 
-    for statement in statements {
-        println!("{:?}", statement);
+    if let LoxError::Runtime(_) = error {
+        for (name, line) in lox.interpreter().stack_trace() {
+            eprintln!("    in {name}() called on line {line}");
+        }
     }
 }
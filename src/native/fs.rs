@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use std::rc::Rc;
+
+use crate::capability::Capability;
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::limits::Allocation;
+use crate::native::NativeFunction;
+use crate::value::Value;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+// Returns the file system functions that are available globally, they fail when called if the
+// script lacks the fs capability
+pub fn globals() -> Vec<NativeFunction> {
+    let natives = [
+        NativeFunction::new("append_file", 2, append_file),
+        NativeFunction::new("file_exists", 1, file_exists),
+        NativeFunction::new("list_dir", 1, list_dir),
+        NativeFunction::new("read_file", 1, read_file),
+        NativeFunction::new("read_lines", 1, read_lines),
+        NativeFunction::new("remove_file", 1, remove_file),
+        NativeFunction::new("write_file", 2, write_file),
+    ];
+
+    natives
+        .into_iter()
+        .map(|native| native.requires(Capability::Fs))
+        .collect()
+}
+
+fn path(arguments: &[Value], line: usize) -> Result<&str, RuntimeError> {
+    string(&arguments[0], line)
+}
+
+fn string(value: &Value, line: usize) -> Result<&str, RuntimeError> {
+    match value {
+        Value::String(string) => Ok(string),
+        _ => Err(RuntimeError::InvalidArgument(
+            format!("Expected a string but got '{value}'"),
+            line,
+        )),
+    }
+}
+
+fn io_error(path: &str, error: &std::io::Error, line: usize) -> RuntimeError {
+    RuntimeError::Io(format!("{path}: {error}"), line)
+}
+
+fn append_file(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let path = path(arguments, line)?;
+    let contents = string(&arguments[1], line)?;
+
+    fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| io_error(path, &e, line))?;
+
+    Ok(Value::Nil)
+}
+
+fn file_exists(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let path = path(arguments, line)?;
+
+    Ok(Value::Bool(fs::exists(path).unwrap_or(false)))
+}
+
+// Returns the sorted names of the entries of the directory
+fn list_dir(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let path = path(arguments, line)?;
+
+    let mut names = fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|e| e.file_name().to_string_lossy().to_string()))
+                .collect::<Result<Vec<String>, _>>()
+        })
+        .map_err(|e| io_error(path, &e, line))?;
+    names.sort();
+    let bytes = names.iter().map(String::len).sum();
+    interpreter.allocate(Allocation::String(bytes), line)?;
+    interpreter.allocate(Allocation::List(names.len()), line)?;
+
+    let names = names.into_iter().map(Value::String).collect();
+
+    Ok(Value::List(Rc::new(RefCell::new(names))))
+}
+
+fn read_file(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let path = path(arguments, line)?;
+
+    let contents = fs::read_to_string(path).map_err(|e| io_error(path, &e, line))?;
+    interpreter.allocate(Allocation::String(contents.len()), line)?;
+
+    Ok(Value::String(contents))
+}
+
+// Returns the lines of the file without line endings, e.g. to iterate over them with 'for in'
+fn read_lines(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let path = path(arguments, line)?;
+
+    let contents = fs::read_to_string(path).map_err(|e| io_error(path, &e, line))?;
+    interpreter.allocate(Allocation::String(contents.len()), line)?;
+
+    let lines: Vec<Value> = contents
+        .lines()
+        .map(|line| Value::String(line.to_string()))
+        .collect();
+    interpreter.allocate(Allocation::List(lines.len()), line)?;
+
+    Ok(Value::List(Rc::new(RefCell::new(lines))))
+}
+
+fn remove_file(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let path = path(arguments, line)?;
+
+    fs::remove_file(path).map_err(|e| io_error(path, &e, line))?;
+
+    Ok(Value::Nil)
+}
+
+fn write_file(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let path = path(arguments, line)?;
+    let contents = string(&arguments[1], line)?;
+
+    fs::write(path, contents).map_err(|e| io_error(path, &e, line))?;
+
+    Ok(Value::Nil)
+}
@@ -0,0 +1,57 @@
+use std::any::Any;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::lox_callable::LoxCallable;
+use crate::value::Value;
+
+type HostFn = dyn Fn(&[Value]) -> Result<Value, String>;
+
+// A function registered by the program embedding the interpreter. The closure is shared between
+// clones of the function, so the state it captures is shared as well.
+#[derive(Clone)]
+pub struct HostFunction {
+    name: String,
+    arity: usize,
+    function: Rc<HostFn>,
+}
+
+impl HostFunction {
+    pub fn new<F, E>(name: &str, arity: usize, function: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value, E> + 'static,
+        E: Display,
+    {
+        Self {
+            name: name.to_string(),
+            arity,
+            function: Rc::new(move |arguments| function(arguments).map_err(|e| e.to_string())),
+        }
+    }
+}
+
+impl LoxCallable for HostFunction {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn call(
+        &self,
+        _: &mut Interpreter,
+        arguments: Vec<Value>,
+        line: usize,
+    ) -> Result<Value, RuntimeError> {
+        (self.function)(&arguments)
+            .map_err(|message| RuntimeError::HostError(self.name.clone(), message, line))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
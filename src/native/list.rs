@@ -0,0 +1,258 @@
+// all natives must match the NativeFn signature, even if they can't fail
+#![allow(clippy::unnecessary_wraps)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::limits::Allocation;
+use crate::native::NativeFunction;
+use crate::value::Value;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+// Returns the list method with the given name, if there is one
+pub fn method(name: &str) -> Option<NativeFunction> {
+    let method = match name {
+        "contains" => NativeFunction::new("contains", 1, contains),
+        "index_of" => NativeFunction::new("index_of", 1, index_of),
+        "insert" => NativeFunction::new("insert", 2, insert),
+        "join" => NativeFunction::new("join", 1, join),
+        "len" => NativeFunction::new("len", 0, len),
+        "pop" => NativeFunction::new("pop", 0, pop),
+        "push" => NativeFunction::new("push", 1, push),
+        "remove" => NativeFunction::new("remove", 1, remove),
+        "reverse" => NativeFunction::new("reverse", 0, reverse),
+        "slice" => NativeFunction::new("slice", 2, slice),
+        "sort" => NativeFunction::new("sort", 0, sort),
+        _ => return None,
+    };
+
+    Some(method)
+}
+
+// Returns the list functions that are available globally
+pub fn globals() -> Vec<NativeFunction> {
+    vec![NativeFunction::new("range", 2, range)]
+}
+
+// Converts a value to an index into a list with the given length. Fails if the value isn't an
+// integer or if it's out of bounds.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn index(value: &Value, len: usize, line: usize) -> Result<usize, RuntimeError> {
+    match value {
+        Value::Number(number) if number.fract() == 0.0 => {
+            if *number >= 0.0 && *number < len as f64 {
+                Ok(*number as usize)
+            } else {
+                Err(RuntimeError::IndexOutOfBounds(*number, len, line))
+            }
+        }
+        _ => Err(RuntimeError::InvalidIndex(value.clone(), line)),
+    }
+}
+
+// Methods are only bound to lists, so the first argument is always a list
+fn receiver(arguments: &[Value]) -> Rc<RefCell<Vec<Value>>> {
+    match &arguments[0] {
+        Value::List(list) => Rc::clone(list),
+        _ => unreachable!("list method called on a value that isn't a list"),
+    }
+}
+
+fn contains(_: &mut Interpreter, arguments: &[Value], _: usize) -> NativeResult {
+    let list = receiver(arguments);
+    let contains = list.borrow().contains(&arguments[1]);
+
+    Ok(Value::Bool(contains))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn index_of(_: &mut Interpreter, arguments: &[Value], _: usize) -> NativeResult {
+    let list = receiver(arguments);
+    let position = list.borrow().iter().position(|v| *v == arguments[1]);
+
+    Ok(position.map_or(Value::Nil, |i| Value::Number(i as f64)))
+}
+
+fn insert(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let list = receiver(arguments);
+    let len = list.borrow().len();
+    // inserting at the end of the list is allowed
+    let i = index(&arguments[1], len + 1, line)?;
+    interpreter.allocate(Allocation::List(1), line)?;
+
+    list.borrow_mut().insert(i, arguments[2].clone());
+
+    Ok(Value::Nil)
+}
+
+// Joins the elements, converted to strings, with the given separator
+fn join(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let Value::String(separator) = &arguments[1] else {
+        return Err(RuntimeError::InvalidArgument(
+            format!("Expected a string but got '{}'", arguments[1]),
+            line,
+        ));
+    };
+
+    let strings: Vec<String> = receiver(arguments)
+        .borrow()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    let joined = strings.join(separator);
+    interpreter.allocate(Allocation::String(joined.len()), line)?;
+
+    Ok(Value::String(joined))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn len(_: &mut Interpreter, arguments: &[Value], _: usize) -> NativeResult {
+    let len = receiver(arguments).borrow().len();
+
+    Ok(Value::Number(len as f64))
+}
+
+fn pop(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let popped = receiver(arguments)
+        .borrow_mut()
+        .pop()
+        .ok_or(RuntimeError::EmptyList(line))?;
+    interpreter.release(Allocation::List(1));
+
+    Ok(popped)
+}
+
+fn push(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    interpreter.allocate(Allocation::List(1), line)?;
+    receiver(arguments).borrow_mut().push(arguments[1].clone());
+
+    Ok(Value::Nil)
+}
+
+fn remove(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let list = receiver(arguments);
+    let len = list.borrow().len();
+    let i = index(&arguments[1], len, line)?;
+
+    let removed = list.borrow_mut().remove(i);
+    interpreter.release(Allocation::List(1));
+
+    Ok(removed)
+}
+
+fn reverse(_: &mut Interpreter, arguments: &[Value], _: usize) -> NativeResult {
+    receiver(arguments).borrow_mut().reverse();
+
+    Ok(Value::Nil)
+}
+
+// Returns a new list with the elements from start (inclusive) to end (exclusive)
+fn slice(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let list = receiver(arguments);
+    let list = list.borrow();
+    let start = index(&arguments[1], list.len() + 1, line)?;
+    let end = index(&arguments[2], list.len() + 1, line)?;
+
+    let elements = if start < end {
+        interpreter.allocate(Allocation::List(end - start), line)?;
+        list[start..end].to_vec()
+    } else {
+        Vec::new()
+    };
+
+    Ok(Value::List(Rc::new(RefCell::new(elements))))
+}
+
+// Sorts the list in place, the list must contain either only numbers or only strings
+fn sort(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let list = receiver(arguments);
+    let mut list = list.borrow_mut();
+
+    if list.iter().all(|v| matches!(v, Value::Number(_))) {
+        list.sort_by(|a, b| match (a, b) {
+            (Value::Number(a), Value::Number(b)) => a.total_cmp(b),
+            _ => unreachable!(),
+        });
+    } else if list.iter().all(|v| matches!(v, Value::String(_))) {
+        list.sort_by(|a, b| match (a, b) {
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            _ => unreachable!(),
+        });
+    } else {
+        return Err(RuntimeError::InvalidArgument(
+            "Only lists of numbers or lists of strings can be sorted".to_string(),
+            line,
+        ));
+    }
+
+    Ok(Value::Nil)
+}
+
+// The maximum number of elements of a list created by range()
+const MAX_RANGE_LEN: usize = 10_000_000;
+
+// Returns a list of the integers from start (inclusive) to end (exclusive)
+#[allow(clippy::cast_possible_truncation)]
+fn range(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let bound = |value: &Value| match value {
+        Value::Number(number) if number.fract() == 0.0 => Ok(*number as i64),
+        _ => Err(RuntimeError::InvalidArgument(
+            format!("Range bounds must be integers, got '{value}'"),
+            line,
+        )),
+    };
+    let (start, end) = (bound(&arguments[0])?, bound(&arguments[1])?);
+    // checked before creating the list, as the range can be arbitrarily large
+    let len = usize::try_from(end.saturating_sub(start)).unwrap_or(0);
+    if len > MAX_RANGE_LEN {
+        return Err(RuntimeError::InvalidArgument(
+            format!("Range of {len} numbers is too large, the maximum is {MAX_RANGE_LEN}"),
+            line,
+        ));
+    }
+    interpreter.allocate(Allocation::List(len), line)?;
+
+    #[allow(clippy::cast_precision_loss)]
+    let numbers = (start..end).map(|i| Value::Number(i as f64)).collect();
+
+    Ok(Value::List(Rc::new(RefCell::new(numbers))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_within_bounds() {
+        assert_eq!(0, index(&Value::Number(0.0), 2, 1).unwrap());
+        assert_eq!(1, index(&Value::Number(1.0), 2, 1).unwrap());
+    }
+
+    #[test]
+    fn index_out_of_bounds() {
+        for number in [-1.0, 2.0] {
+            match index(&Value::Number(number), 2, 1) {
+                Err(RuntimeError::IndexOutOfBounds(_, 2, 1)) => {}
+                _ => panic!("unexpected result"),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_index() {
+        for value in [Value::Number(0.5), Value::Nil] {
+            match index(&value, 2, 1) {
+                Err(RuntimeError::InvalidIndex(_, 1)) => {}
+                _ => panic!("unexpected result"),
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// all natives must match the NativeFn signature, even if they can't fail
+#![allow(clippy::unnecessary_wraps)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::limits::Allocation;
+use crate::lox_map::{LoxMap, MapKey};
+use crate::native::NativeFunction;
+use crate::value::Value;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+// Returns the map method with the given name, if there is one
+pub fn method(name: &str) -> Option<NativeFunction> {
+    let method = match name {
+        "has" => NativeFunction::new("has", 1, has),
+        "keys" => NativeFunction::new("keys", 0, keys),
+        "len" => NativeFunction::new("len", 0, len),
+        "remove" => NativeFunction::new("remove", 1, remove),
+        "values" => NativeFunction::new("values", 0, values),
+        _ => return None,
+    };
+
+    Some(method)
+}
+
+// Converts a value to a map key. Fails if the value can't be used as a key.
+pub fn key(value: &Value, line: usize) -> Result<MapKey, RuntimeError> {
+    MapKey::from_value(value).ok_or_else(|| RuntimeError::InvalidKey(value.clone(), line))
+}
+
+// Methods are only bound to maps, so the first argument is always a map
+fn receiver(arguments: &[Value]) -> Rc<RefCell<LoxMap>> {
+    match &arguments[0] {
+        Value::Map(map) => Rc::clone(map),
+        _ => unreachable!("map method called on a value that isn't a map"),
+    }
+}
+
+fn has(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let key = key(&arguments[1], line)?;
+    let has = receiver(arguments).borrow().contains_key(&key);
+
+    Ok(Value::Bool(has))
+}
+
+fn keys(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let map = receiver(arguments);
+    interpreter.allocate(Allocation::List(map.borrow().len()), line)?;
+
+    let keys = map.borrow().iter().map(|(key, _)| key.to_value()).collect();
+
+    Ok(Value::List(Rc::new(RefCell::new(keys))))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn len(_: &mut Interpreter, arguments: &[Value], _: usize) -> NativeResult {
+    let len = receiver(arguments).borrow().len();
+
+    Ok(Value::Number(len as f64))
+}
+
+// Returns the removed value, or nil if there was no entry with the given key
+fn remove(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let key = key(&arguments[1], line)?;
+    let removed = receiver(arguments).borrow_mut().remove(&key);
+
+    if removed.is_some() {
+        interpreter.release(Allocation::Map(1));
+    }
+    Ok(removed.unwrap_or(Value::Nil))
+}
+
+fn values(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let map = receiver(arguments);
+    interpreter.allocate(Allocation::List(map.borrow().len()), line)?;
+
+    let values = map
+        .borrow()
+        .iter()
+        .map(|(_, value)| value.clone())
+        .collect();
+
+    Ok(Value::List(Rc::new(RefCell::new(values))))
+}
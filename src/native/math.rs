@@ -0,0 +1,183 @@
+use std::f64::consts;
+
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::native::NativeFunction;
+use crate::value::Value;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+// Returns the math functions that are available globally
+pub fn globals() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction::new("abs", 1, abs),
+        NativeFunction::new("atan2", 2, atan2),
+        NativeFunction::new("ceil", 1, ceil),
+        NativeFunction::new("cos", 1, cos),
+        NativeFunction::new("div", 2, div),
+        NativeFunction::new("exp", 1, exp),
+        NativeFunction::new("floor", 1, floor),
+        NativeFunction::new("log", 1, log),
+        NativeFunction::new("max", 2, max),
+        NativeFunction::new("min", 2, min),
+        NativeFunction::new("mod", 2, modulo),
+        NativeFunction::new("pow", 2, pow),
+        NativeFunction::new("round", 1, round),
+        NativeFunction::new("sin", 1, sin),
+        NativeFunction::new("sqrt", 1, sqrt),
+        NativeFunction::new("tan", 1, tan),
+    ]
+}
+
+// Returns the math constants that are available globally
+pub fn constants() -> Vec<(&'static str, Value)> {
+    vec![
+        ("E", Value::Number(consts::E)),
+        ("PI", Value::Number(consts::PI)),
+    ]
+}
+
+// Converts an argument to a number. Fails if the argument isn't a number.
+pub fn number(value: &Value, line: usize) -> Result<f64, RuntimeError> {
+    match value {
+        Value::Number(number) => Ok(*number),
+        _ => Err(RuntimeError::InvalidArgument(
+            format!("Expected a number but got '{value}'"),
+            line,
+        )),
+    }
+}
+
+fn unary(arguments: &[Value], line: usize, function: fn(f64) -> f64) -> NativeResult {
+    Ok(Value::Number(function(number(&arguments[0], line)?)))
+}
+
+fn binary(arguments: &[Value], line: usize, function: fn(f64, f64) -> f64) -> NativeResult {
+    let a = number(&arguments[0], line)?;
+    let b = number(&arguments[1], line)?;
+
+    Ok(Value::Number(function(a, b)))
+}
+
+fn abs(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::abs)
+}
+
+fn atan2(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    binary(arguments, line, f64::atan2)
+}
+
+fn ceil(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::ceil)
+}
+
+fn cos(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::cos)
+}
+
+// Integer division, rounding towards negative infinity like 'mod'
+fn div(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    if number(&arguments[1], line)? == 0.0 {
+        return Err(RuntimeError::InvalidArgument(
+            "Division by zero".to_string(),
+            line,
+        ));
+    }
+
+    binary(arguments, line, |a, b| (a / b).floor())
+}
+
+fn exp(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::exp)
+}
+
+fn floor(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::floor)
+}
+
+// The natural logarithm
+fn log(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::ln)
+}
+
+fn max(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    binary(arguments, line, f64::max)
+}
+
+fn min(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    binary(arguments, line, f64::min)
+}
+
+// The remainder of 'div', it has the sign of the divisor, so that a == div(a, b) * b + mod(a, b)
+fn modulo(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    if number(&arguments[1], line)? == 0.0 {
+        return Err(RuntimeError::InvalidArgument(
+            "Division by zero".to_string(),
+            line,
+        ));
+    }
+
+    binary(arguments, line, |a, b| b.mul_add(-(a / b).floor(), a))
+}
+
+fn pow(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    binary(arguments, line, f64::powf)
+}
+
+// Rounds half-way cases away from zero
+fn round(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::round)
+}
+
+fn sin(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::sin)
+}
+
+fn sqrt(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::sqrt)
+}
+
+fn tan(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    unary(arguments, line, f64::tan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn div_and_mod() {
+        let mut interpreter = Interpreter::new();
+        let cases = [
+            (7.0, 2.0, 3.0, 1.0),
+            (-7.0, 2.0, -4.0, 1.0),
+            (7.0, -2.0, -4.0, -1.0),
+        ];
+
+        for (a, b, quotient, remainder) in cases {
+            let arguments = [Value::Number(a), Value::Number(b)];
+            assert_eq!(
+                Value::Number(quotient),
+                div(&mut interpreter, &arguments, 1).unwrap()
+            );
+            assert_eq!(
+                Value::Number(remainder),
+                modulo(&mut interpreter, &arguments, 1).unwrap()
+            );
+        }
+
+        let arguments = [Value::Number(1.0), Value::Number(0.0)];
+        assert!(div(&mut interpreter, &arguments, 1).is_err());
+        assert!(modulo(&mut interpreter, &arguments, 1).is_err());
+    }
+
+    #[test]
+    fn non_number_argument() {
+        let arguments = [Value::String("1".to_string())];
+
+        match sqrt(&mut Interpreter::new(), &arguments, 3) {
+            Err(RuntimeError::InvalidArgument(_, 3)) => {}
+            _ => panic!("unexpected result"),
+        }
+    }
+}
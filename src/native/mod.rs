@@ -0,0 +1,83 @@
+use std::any::Any;
+
+use crate::capability::Capability;
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::lox_callable::LoxCallable;
+use crate::value::Value;
+
+pub mod fs;
+pub mod host;
+pub mod list;
+pub mod map;
+pub mod math;
+pub mod random;
+pub mod string;
+pub mod system;
+pub mod types;
+
+pub type NativeFn = fn(&mut Interpreter, &[Value], usize) -> Result<Value, RuntimeError>;
+
+#[derive(Clone)]
+pub struct NativeFunction {
+    name: &'static str,
+    arity: usize,
+    function: NativeFn,
+    // the value a method was looked up on, it's passed to the function as first argument
+    receiver: Option<Value>,
+    // the capability a script needs to call the function, if any
+    capability: Option<Capability>,
+}
+
+impl NativeFunction {
+    pub const fn new(name: &'static str, arity: usize, function: NativeFn) -> Self {
+        Self {
+            name,
+            arity,
+            function,
+            receiver: None,
+            capability: None,
+        }
+    }
+
+    pub const fn requires(mut self, capability: Capability) -> Self {
+        self.capability = Some(capability);
+        self
+    }
+
+    pub fn bind(mut self, receiver: Value) -> Self {
+        self.receiver = Some(receiver);
+        self
+    }
+}
+
+impl LoxCallable for NativeFunction {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        mut arguments: Vec<Value>,
+        line: usize,
+    ) -> Result<Value, RuntimeError> {
+        if let Some(capability) = self.capability {
+            interpreter.require(capability, self.name, line)?;
+        }
+
+        if let Some(receiver) = &self.receiver {
+            arguments.insert(0, receiver.clone());
+        }
+
+        (self.function)(interpreter, &arguments, line)
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
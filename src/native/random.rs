@@ -0,0 +1,63 @@
+use crate::capability::Capability;
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::native::math::number;
+use crate::native::NativeFunction;
+use crate::value::Value;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+// Returns the random number functions that are available globally, if the script may use them
+pub fn globals() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction::new("random", 0, random).requires(Capability::Random),
+        NativeFunction::new("random_int", 2, random_int).requires(Capability::Random),
+    ]
+}
+
+// Returns a random number in [0, 1)
+#[allow(clippy::unnecessary_wraps)]
+fn random(interpreter: &mut Interpreter, _: &[Value], _: usize) -> NativeResult {
+    Ok(Value::Number(interpreter.rng().next_f64()))
+}
+
+// Returns a random integer from min to max, both inclusive
+fn random_int(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let (min, max) = (number(&arguments[0], line)?, number(&arguments[1], line)?);
+
+    if min.fract() != 0.0 || max.fract() != 0.0 || min > max {
+        return Err(RuntimeError::InvalidArgument(
+            format!("Expected integers with min <= max but got '{min}' and '{max}'"),
+            line,
+        ));
+    }
+
+    let offset = (interpreter.rng().next_f64() * (max - min + 1.0)).floor();
+
+    Ok(Value::Number(min + offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_ints_within_bounds() {
+        let mut interpreter = Interpreter::new();
+        let arguments = [Value::Number(-2.0), Value::Number(2.0)];
+        let mut seen = Vec::new();
+
+        for _ in 0..200 {
+            match random_int(&mut interpreter, &arguments, 1).unwrap() {
+                Value::Number(n) if (-2.0..=2.0).contains(&n) && n.fract() == 0.0 => seen.push(n),
+                value => panic!("unexpected value {value}"),
+            }
+        }
+        for n in [-2.0, -1.0, 0.0, 1.0, 2.0] {
+            assert!(seen.contains(&n));
+        }
+
+        let arguments = [Value::Number(2.0), Value::Number(1.0)];
+        assert!(random_int(&mut interpreter, &arguments, 1).is_err());
+    }
+}
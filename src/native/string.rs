@@ -0,0 +1,245 @@
+// all natives must match the NativeFn signature, even if they can't fail
+#![allow(clippy::unnecessary_wraps)]
+
+// Strings are indexed by Unicode scalar values (chars), not by bytes or grapheme clusters, e.g.
+// "é" has length 1 if it's written as a single code point, but length 2 if it's written as 'e'
+// followed by a combining accent.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::limits::Allocation;
+use crate::native::list::index;
+use crate::native::NativeFunction;
+use crate::value::Value;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+// Returns the string method with the given name, if there is one
+pub fn method(name: &str) -> Option<NativeFunction> {
+    let method = match name {
+        "chars" => NativeFunction::new("chars", 0, chars),
+        "contains" => NativeFunction::new("contains", 1, contains),
+        "ends_with" => NativeFunction::new("ends_with", 1, ends_with),
+        "index_of" => NativeFunction::new("index_of", 1, index_of),
+        "len" => NativeFunction::new("len", 0, len),
+        "lower" => NativeFunction::new("lower", 0, lower),
+        "repeat" => NativeFunction::new("repeat", 1, repeat),
+        "replace" => NativeFunction::new("replace", 2, replace),
+        "split" => NativeFunction::new("split", 1, split),
+        "starts_with" => NativeFunction::new("starts_with", 1, starts_with),
+        "substring" => NativeFunction::new("substring", 2, substring),
+        "trim" => NativeFunction::new("trim", 0, trim),
+        "upper" => NativeFunction::new("upper", 0, upper),
+        _ => return None,
+    };
+
+    Some(method)
+}
+
+// Returns the char at the given index as a string
+pub fn char_at(
+    interpreter: &mut Interpreter,
+    string: &str,
+    i: &Value,
+    line: usize,
+) -> NativeResult {
+    let i = index(i, string.chars().count(), line)?;
+
+    new_string(interpreter, string.chars().skip(i).take(1).collect(), line)
+}
+
+// Methods are only bound to strings, so the first argument is always a string
+fn receiver(arguments: &[Value]) -> &str {
+    match &arguments[0] {
+        Value::String(string) => string,
+        _ => unreachable!("string method called on a value that isn't a string"),
+    }
+}
+
+fn string_argument(value: &Value, line: usize) -> Result<&str, RuntimeError> {
+    match value {
+        Value::String(string) => Ok(string),
+        _ => Err(RuntimeError::InvalidArgument(
+            format!("Expected a string but got '{value}'"),
+            line,
+        )),
+    }
+}
+
+fn new_string(interpreter: &mut Interpreter, string: String, line: usize) -> NativeResult {
+    interpreter.allocate(Allocation::String(string.len()), line)?;
+
+    Ok(Value::String(string))
+}
+
+fn list(interpreter: &mut Interpreter, strings: Vec<String>, line: usize) -> NativeResult {
+    let bytes = strings.iter().map(String::len).sum();
+    interpreter.allocate(Allocation::String(bytes), line)?;
+    interpreter.allocate(Allocation::List(strings.len()), line)?;
+
+    let elements = strings.into_iter().map(Value::String).collect();
+
+    Ok(Value::List(Rc::new(RefCell::new(elements))))
+}
+
+fn chars(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let chars = receiver(arguments).chars().map(String::from).collect();
+
+    list(interpreter, chars, line)
+}
+
+fn contains(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let pattern = string_argument(&arguments[1], line)?;
+
+    Ok(Value::Bool(receiver(arguments).contains(pattern)))
+}
+
+fn ends_with(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let pattern = string_argument(&arguments[1], line)?;
+
+    Ok(Value::Bool(receiver(arguments).ends_with(pattern)))
+}
+
+// Returns the char index of the first occurrence of the pattern, or nil if there is none
+#[allow(clippy::cast_precision_loss)]
+fn index_of(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let string = receiver(arguments);
+    let pattern = string_argument(&arguments[1], line)?;
+
+    Ok(string.find(pattern).map_or(Value::Nil, |byte_index| {
+        Value::Number(string[..byte_index].chars().count() as f64)
+    }))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn len(_: &mut Interpreter, arguments: &[Value], _: usize) -> NativeResult {
+    Ok(Value::Number(receiver(arguments).chars().count() as f64))
+}
+
+fn lower(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    new_string(interpreter, receiver(arguments).to_lowercase(), line)
+}
+
+// The maximum length in bytes of a string created by repeat()
+const MAX_REPEAT_LEN: usize = 100_000_000;
+
+fn repeat(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let count = match &arguments[1] {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Value::Number(count) if count.fract() == 0.0 && *count >= 0.0 => *count as usize,
+        count => {
+            return Err(RuntimeError::InvalidArgument(
+                format!("Expected a non-negative integer but got '{count}'"),
+                line,
+            ))
+        }
+    };
+
+    let string = receiver(arguments);
+    // checked before repeating, as the result can be arbitrarily large
+    let len = string
+        .len()
+        .checked_mul(count)
+        .filter(|len| *len <= MAX_REPEAT_LEN)
+        .ok_or_else(|| {
+            RuntimeError::InvalidArgument(
+                format!("Repeated string is too large, the maximum is {MAX_REPEAT_LEN} bytes"),
+                line,
+            )
+        })?;
+    interpreter.allocate(Allocation::String(len), line)?;
+
+    Ok(Value::String(string.repeat(count)))
+}
+
+fn replace(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let from = string_argument(&arguments[1], line)?;
+    let to = string_argument(&arguments[2], line)?;
+
+    new_string(interpreter, receiver(arguments).replace(from, to), line)
+}
+
+// Splitting by the empty string results in the chars of the string
+fn split(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let string = receiver(arguments);
+    let separator = string_argument(&arguments[1], line)?;
+
+    let parts = if separator.is_empty() {
+        string.chars().map(String::from).collect()
+    } else {
+        string.split(separator).map(String::from).collect()
+    };
+
+    list(interpreter, parts, line)
+}
+
+fn starts_with(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let pattern = string_argument(&arguments[1], line)?;
+
+    Ok(Value::Bool(receiver(arguments).starts_with(pattern)))
+}
+
+// Returns the chars from start (inclusive) to end (exclusive)
+fn substring(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let string = receiver(arguments);
+    let len = string.chars().count();
+    let start = index(&arguments[1], len + 1, line)?;
+    let end = index(&arguments[2], len + 1, line)?;
+
+    let substring = string
+        .chars()
+        .skip(start)
+        .take(end.saturating_sub(start))
+        .collect();
+
+    new_string(interpreter, substring, line)
+}
+
+fn trim(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    new_string(interpreter, receiver(arguments).trim().to_string(), line)
+}
+
+fn upper(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    new_string(interpreter, receiver(arguments).to_uppercase(), line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_based_indices() {
+        let mut interpreter = Interpreter::new();
+        let string = Value::String("héllo wörld".to_string());
+        let call = |interpreter: &mut Interpreter, name: &str, arguments: &[Value]| {
+            let mut all_arguments = vec![string.clone()];
+            all_arguments.extend_from_slice(arguments);
+            (method(name).unwrap().function)(interpreter, &all_arguments, 1).unwrap()
+        };
+
+        assert_eq!(Value::Number(11.0), call(&mut interpreter, "len", &[]));
+        assert_eq!(
+            Value::Number(7.0),
+            call(
+                &mut interpreter,
+                "index_of",
+                &[Value::String("ö".to_string())]
+            )
+        );
+        assert_eq!(
+            Value::String("éllo".to_string()),
+            call(
+                &mut interpreter,
+                "substring",
+                &[Value::Number(1.0), Value::Number(5.0)]
+            )
+        );
+        assert_eq!(
+            Value::String("ö".to_string()),
+            char_at(&mut interpreter, "wörld", &Value::Number(1.0), 1).unwrap()
+        );
+    }
+}
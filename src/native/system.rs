@@ -0,0 +1,84 @@
+// all natives must match the NativeFn signature, even if they can't fail
+#![allow(clippy::unnecessary_wraps)]
+
+use std::cell::RefCell;
+use std::env;
+use std::io;
+use std::rc::Rc;
+
+use crate::capability::Capability;
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::limits::Allocation;
+use crate::native::NativeFunction;
+use crate::value::Value;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+// Returns the functions interacting with the process and its environment that are available
+// globally
+pub fn globals() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction::new("args", 0, args).requires(Capability::Process),
+        NativeFunction::new("getenv", 1, getenv).requires(Capability::Env),
+        NativeFunction::new("input", 1, input).requires(Capability::Process),
+        NativeFunction::new("read_line", 0, read_line).requires(Capability::Process),
+    ]
+}
+
+// Returns the command-line arguments after the script
+fn args(interpreter: &mut Interpreter, _: &[Value], line: usize) -> NativeResult {
+    let args = interpreter.script_args().to_vec();
+    let bytes = args.iter().map(String::len).sum();
+    interpreter.allocate(Allocation::String(bytes), line)?;
+    interpreter.allocate(Allocation::List(args.len()), line)?;
+
+    let args = args.into_iter().map(Value::String).collect();
+
+    Ok(Value::List(Rc::new(RefCell::new(args))))
+}
+
+// Returns the value of the environment variable, or nil if it isn't set
+fn getenv(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let Value::String(name) = &arguments[0] else {
+        return Err(RuntimeError::InvalidArgument(
+            format!("Expected a string but got '{}'", arguments[0]),
+            line,
+        ));
+    };
+
+    let Ok(value) = env::var(name) else {
+        return Ok(Value::Nil);
+    };
+    interpreter.allocate(Allocation::String(value.len()), line)?;
+
+    Ok(Value::String(value))
+}
+
+// Prints the prompt and reads a line
+fn input(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let output = interpreter.output();
+    write!(output, "{}", arguments[0])
+        .and_then(|()| output.flush())
+        .map_err(|e| RuntimeError::Io(e.to_string(), line))?;
+
+    read_line(interpreter, &[], line)
+}
+
+// Returns the next line of stdin without the line ending, or nil at the end of the input
+fn read_line(interpreter: &mut Interpreter, _: &[Value], line: usize) -> NativeResult {
+    let mut input = String::new();
+    let read = io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| RuntimeError::Io(e.to_string(), line))?;
+
+    if read == 0 {
+        return Ok(Value::Nil);
+    }
+
+    let len = input.trim_end_matches(['\n', '\r']).len();
+    input.truncate(len);
+    interpreter.allocate(Allocation::String(len), line)?;
+
+    Ok(Value::String(input))
+}
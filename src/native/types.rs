@@ -0,0 +1,107 @@
+// all natives must match the NativeFn signature, even if they can't fail
+#![allow(clippy::unnecessary_wraps)]
+
+use std::rc::Rc;
+
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::limits::Allocation;
+use crate::native::NativeFunction;
+use crate::value::Value;
+
+type NativeResult = Result<Value, RuntimeError>;
+
+// Returns the conversion and introspection functions that are available globally
+pub fn globals() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction::new("is_instance", 2, is_instance),
+        NativeFunction::new("num", 1, num),
+        NativeFunction::new("str", 1, str),
+        NativeFunction::new("type", 1, type_name),
+    ]
+}
+
+fn is_instance(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let Value::Class(class) = &arguments[1] else {
+        return Err(RuntimeError::InvalidArgument(
+            format!("Expected a class but got '{}'", arguments[1]),
+            line,
+        ));
+    };
+
+    let is_instance = match &arguments[0] {
+        Value::Instance(instance) => Rc::ptr_eq(instance.borrow().class(), class),
+        _ => false,
+    };
+
+    Ok(Value::Bool(is_instance))
+}
+
+// Converts a string to a number, using the syntax of number literals with an optional sign and
+// exponent. Returns nil if the string isn't a number.
+fn num(_: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    match &arguments[0] {
+        Value::Number(number) => Ok(Value::Number(*number)),
+        Value::String(string) => {
+            let string = string.trim();
+            // excludes "inf" and "NaN", which Rust can parse but aren't Lox numbers
+            let is_numeric = string
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E'));
+
+            Ok(string
+                .parse()
+                .ok()
+                .filter(|_| is_numeric)
+                .map_or(Value::Nil, Value::Number))
+        }
+        value => Err(RuntimeError::InvalidArgument(
+            format!("Expected a number or a string but got '{value}'"),
+            line,
+        )),
+    }
+}
+
+// Converts a value to a string the same way 'print' does
+fn str(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let string = arguments[0].to_string();
+    interpreter.allocate(Allocation::String(string.len()), line)?;
+
+    Ok(Value::String(string))
+}
+
+fn type_name(interpreter: &mut Interpreter, arguments: &[Value], line: usize) -> NativeResult {
+    let name = arguments[0].type_name();
+    interpreter.allocate(Allocation::String(name.len()), line)?;
+
+    Ok(Value::String(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_strings_to_numbers() {
+        let strings_and_expectations = vec![
+            ("42", Value::Number(42.0)),
+            (" -1.5e3 ", Value::Number(-1500.0)),
+            ("4x", Value::Nil),
+            ("inf", Value::Nil),
+            ("", Value::Nil),
+        ];
+
+        for (string, expected) in strings_and_expectations {
+            let arguments = [Value::String(string.to_string())];
+            assert_eq!(
+                expected,
+                num(&mut Interpreter::new(), &arguments, 1).unwrap()
+            );
+        }
+
+        match num(&mut Interpreter::new(), &[Value::Bool(true)], 1) {
+            Err(RuntimeError::InvalidArgument(_, 1)) => {}
+            _ => panic!("unexpected result"),
+        }
+    }
+}
@@ -10,29 +10,49 @@ type ParseResult<T> = Result<T, ParseError>;
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    // number of loops enclosing the current statement, used to validate 'break' and 'continue'
+    loop_depth: usize,
+    // errors after which parsing can continue as if the code was valid
+    errors: Vec<ParseError>,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Parser {
+    #[must_use]
     pub const fn new() -> Self {
         Self {
             tokens: Vec::new(),
             current: 0,
+            loop_depth: 0,
+            errors: Vec::new(),
         }
     }
 
+    /// Parses the tokens into statements.
+    ///
+    /// # Errors
+    ///
+    /// Fails with all the syntax errors found in the tokens.
     pub fn parse(&mut self, tokens: Vec<Token>) -> Result<Vec<Stmt>, Vec<ParseError>> {
         self.tokens = tokens;
         self.current = 0;
+        self.loop_depth = 0;
+        self.errors = Vec::new();
         let mut statements = Vec::new();
-        let mut errors = Vec::new();
 
         while !self.is_at_end() {
-            match self.declaration() {
+            match self.top_level_declaration() {
                 Ok(statement) => statements.push(statement),
-                Err(e) => errors.push(e),
+                Err(e) => self.errors.push(e),
             }
         }
 
+        let errors = std::mem::take(&mut self.errors);
         if errors.is_empty() {
             Ok(statements)
         } else {
@@ -40,11 +60,67 @@ impl Parser {
         }
     }
 
+    /// Parses the tokens into a single expression, e.g. to evaluate a condition provided by a
+    /// user. Statements aren't allowed, not even expression statements.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the tokens aren't a valid expression, or if there are tokens after it.
+    pub fn parse_expression(&mut self, tokens: Vec<Token>) -> Result<Expr, Vec<ParseError>> {
+        self.tokens = tokens;
+        self.current = 0;
+        self.loop_depth = 0;
+        self.errors = Vec::new();
+
+        if self.is_at_end() {
+            return Err(vec![ParseError::MissingExpression(self.peek())]);
+        }
+
+        let expr = self.expression().map_err(|e| vec![e])?;
+
+        let mut errors = std::mem::take(&mut self.errors);
+        if !self.is_at_end() {
+            errors.push(ParseError::UnexpectedTokenAfterExpression(self.peek()));
+        }
+
+        if errors.is_empty() {
+            Ok(expr)
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn top_level_declaration(&mut self) -> ParseResult<Stmt> {
+        if !self.do_match(vec![TokenType::Export]) {
+            return self.declaration();
+        }
+
+        let keyword = self.previous();
+        let is_declaration = self.check(&TokenType::Class)
+            || self.check(&TokenType::Var)
+            || (self.check(&TokenType::Fun) && self.peek_at(1).token_type != TokenType::LeftParen);
+
+        if is_declaration {
+            Ok(Stmt::Export(keyword, Box::new(self.declaration()?)))
+        } else {
+            Err(ParseError::MissingDeclarationAfterExport(keyword))
+        }
+    }
+
     fn declaration(&mut self) -> ParseResult<Stmt> {
-        if self.do_match(vec![TokenType::Class]) {
+        if self.check(&TokenType::Export) {
+            Err(ParseError::ExportNotAtTopLevel(self.advance()))
+        } else if self.do_match(vec![TokenType::Class]) {
             self.class_declaration()
-        } else if self.do_match(vec![TokenType::Fun]) {
+        } else if self.check(&TokenType::Fun) && self.peek_at(1).token_type != TokenType::LeftParen
+        {
+            // 'fun' followed by '(' starts a lambda expression and not a function declaration
+            self.advance();
             self.function("function")
+        } else if self.do_match(vec![TokenType::From]) {
+            self.import_from_declaration()
+        } else if self.do_match(vec![TokenType::Import]) {
+            self.import_declaration()
         } else if self.do_match(vec![TokenType::Var]) {
             self.var_declaration()
         } else {
@@ -80,6 +156,37 @@ impl Parser {
             TokenType::LeftParen,
             ParseError::MissingParenAfterName(name.clone(), kind.to_string()),
         )?;
+        let parameters = self.parameters()?;
+        let body = self.function_body(kind)?;
+
+        Ok(Stmt::Function(name, parameters, body))
+    }
+
+    fn lambda(&mut self) -> ParseResult<Expr> {
+        let keyword = self.previous();
+
+        self.consume(
+            TokenType::LeftParen,
+            ParseError::MissingParenAfterFun(keyword.clone()),
+        )?;
+        let params = self.parameters()?;
+
+        let body = if self.do_match(vec![TokenType::Arrow]) {
+            let arrow = self.previous();
+            vec![Stmt::Return(arrow, Some(self.expression()?))]
+        } else {
+            self.function_body("lambda")?
+        };
+
+        Ok(Expr::Lambda {
+            keyword,
+            params,
+            body,
+        })
+    }
+
+    // Parses the parameter list of a function, including the closing parenthesis
+    fn parameters(&mut self) -> ParseResult<Vec<Token>> {
         let mut parameters = Vec::new();
 
         if !self.check(&TokenType::RightParen) {
@@ -100,19 +207,96 @@ impl Parser {
             TokenType::RightParen,
             ParseError::MissingParenAfterParameters(self.previous()),
         )?;
+
+        Ok(parameters)
+    }
+
+    fn function_body(&mut self, kind: &str) -> ParseResult<Vec<Stmt>> {
+        // loops outside of the function body can't be left from inside the function body
+        let enclosing_loop_depth = self.loop_depth;
+        self.loop_depth = 0;
+        let body = self.body(kind);
+        self.loop_depth = enclosing_loop_depth;
+
+        body
+    }
+
+    // Parses a block that must follow a keyword, e.g. the body of a function or a 'try' block
+    fn body(&mut self, kind: &str) -> ParseResult<Vec<Stmt>> {
         self.consume(
             TokenType::LeftBrace,
             ParseError::MissingBraceBeforeBody(self.previous(), kind.to_string()),
         )?;
 
         if let Stmt::Block(body) = self.block_statement()? {
-            Ok(Stmt::Function(name, parameters, body))
+            Ok(body)
         } else {
             // unreachable code, needed to make the compiler happy
             Err(ParseError::UnexpectedError)
         }
     }
 
+    fn import_declaration(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
+        let path = self.module_path()?;
+
+        let alias = if self.do_match(vec![TokenType::As]) {
+            Some(self.consume_identifier(ParseError::MissingName(
+                self.previous(),
+                "module".to_string(),
+            ))?)
+        } else {
+            None
+        };
+
+        self.consume(
+            TokenType::Semicolon,
+            ParseError::MissingSemicolonAfterImport(self.previous()),
+        )?;
+
+        Ok(Stmt::Import(keyword, path, alias))
+    }
+
+    fn import_from_declaration(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
+        let path = self.module_path()?;
+
+        self.consume(
+            TokenType::Import,
+            ParseError::MissingImportAfterModulePath(self.previous()),
+        )?;
+
+        let mut names = Vec::new();
+        loop {
+            names.push(self.consume_identifier(ParseError::MissingName(
+                self.previous(),
+                "imported".to_string(),
+            ))?);
+
+            if !self.do_match(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(
+            TokenType::Semicolon,
+            ParseError::MissingSemicolonAfterImport(self.previous()),
+        )?;
+
+        Ok(Stmt::ImportFrom(keyword, path, names))
+    }
+
+    // Parses the path of an imported module, the keyword before the path was already consumed
+    fn module_path(&mut self) -> ParseResult<String> {
+        match self.peek().token_type {
+            TokenType::String(path) => {
+                self.advance();
+                Ok(path)
+            }
+            _ => Err(ParseError::MissingModulePath(self.previous())),
+        }
+    }
+
     fn var_declaration(&mut self) -> ParseResult<Stmt> {
         let name = self.consume_identifier(ParseError::MissingVariableName(self.previous()))?;
 
@@ -130,7 +314,9 @@ impl Parser {
     }
 
     fn statement(&mut self) -> ParseResult<Stmt> {
-        if self.do_match(vec![TokenType::For]) {
+        if self.do_match(vec![TokenType::Break, TokenType::Continue]) {
+            self.loop_control_statement()
+        } else if self.do_match(vec![TokenType::For]) {
             self.for_statement()
         } else if self.do_match(vec![TokenType::If]) {
             self.if_statement()
@@ -138,6 +324,10 @@ impl Parser {
             self.print_statement()
         } else if self.do_match(vec![TokenType::Return]) {
             self.return_statement()
+        } else if self.do_match(vec![TokenType::Throw]) {
+            self.throw_statement()
+        } else if self.do_match(vec![TokenType::Try]) {
+            self.try_statement()
         } else if self.do_match(vec![TokenType::While]) {
             self.while_statement()
         } else if self.do_match(vec![TokenType::LeftBrace]) {
@@ -147,6 +337,50 @@ impl Parser {
         }
     }
 
+    fn throw_statement(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
+        let value = self.expression()?;
+
+        self.consume(
+            TokenType::Semicolon,
+            ParseError::MissingSemicolonAfterValue(self.previous()),
+        )?;
+
+        Ok(Stmt::Throw(keyword, value))
+    }
+
+    fn try_statement(&mut self) -> ParseResult<Stmt> {
+        let body = self.body("try")?;
+
+        let catch = if self.do_match(vec![TokenType::Catch]) {
+            self.consume(
+                TokenType::LeftParen,
+                ParseError::MissingParenAfterCatch(self.previous()),
+            )?;
+            let name = self.consume_identifier(ParseError::MissingVariableName(self.previous()))?;
+            self.consume(
+                TokenType::RightParen,
+                ParseError::MissingParenAfterCatchVariable(self.previous()),
+            )?;
+
+            Some((name, self.body("catch")?))
+        } else {
+            None
+        };
+
+        let finally = if self.do_match(vec![TokenType::Finally]) {
+            Some(self.body("finally")?)
+        } else {
+            None
+        };
+
+        if catch.is_none() && finally.is_none() {
+            return Err(ParseError::MissingCatchOrFinally(self.previous()));
+        }
+
+        Ok(Stmt::Try(body, catch, finally))
+    }
+
     fn return_statement(&mut self) -> ParseResult<Stmt> {
         let keyword = self.previous();
 
@@ -164,12 +398,39 @@ impl Parser {
         Ok(Stmt::Return(keyword, value))
     }
 
+    fn loop_control_statement(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
+
+        // the statement is well-formed, so the parser isn't confused and can go on
+        if self.loop_depth == 0 {
+            self.errors
+                .push(ParseError::KeywordOutsideLoop(keyword.clone()));
+        }
+
+        self.consume(
+            TokenType::Semicolon,
+            ParseError::MissingSemicolonAfterKeyword(keyword.clone()),
+        )?;
+
+        if keyword.token_type == TokenType::Break {
+            Ok(Stmt::Break(keyword))
+        } else {
+            Ok(Stmt::Continue(keyword))
+        }
+    }
+
     fn for_statement(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
         self.consume(
             TokenType::LeftParen,
             ParseError::MissingParenAfterFor(self.previous()),
         )?;
 
+        if self.check(&TokenType::Var) && self.peek_at(2).token_type == TokenType::In {
+            self.advance();
+            return self.for_in_statement();
+        }
+
         let initializer = if self.do_match(vec![TokenType::Semicolon]) {
             None
         } else if self.do_match(vec![TokenType::Var]) {
@@ -198,25 +459,38 @@ impl Parser {
             ParseError::MissingParenAfterForClauses(self.previous()),
         )?;
 
-        let mut body = self.statement()?;
-
-        if increment != None {
-            body = Stmt::Block(vec![body, Stmt::Expr(increment.unwrap())]);
-        }
+        let body = self.loop_body()?;
 
-        if condition == None {
+        if condition.is_none() {
             condition = Some(Expr::Literal(Literal::Bool(true)));
         }
-        body = Stmt::While(condition.unwrap(), Box::new(body));
+        // the increment is kept separate from the body so it also runs after a 'continue'
+        let mut body = Stmt::While(keyword, condition.unwrap(), Box::new(body), increment);
 
-        if initializer != None {
-            body = Stmt::Block(vec![initializer.unwrap(), body]);
+        if let Some(initializer) = initializer {
+            body = Stmt::Block(vec![initializer, body]);
         }
 
         Ok(body)
     }
 
+    fn for_in_statement(&mut self) -> ParseResult<Stmt> {
+        let name = self.consume_identifier(ParseError::MissingVariableName(self.previous()))?;
+        self.consume(TokenType::In, ParseError::UnexpectedError)?;
+
+        let iterable = self.expression()?;
+        self.consume(
+            TokenType::RightParen,
+            ParseError::MissingParenAfterForClauses(self.previous()),
+        )?;
+
+        let body = self.loop_body()?;
+
+        Ok(Stmt::ForIn(name, iterable, Box::new(body)))
+    }
+
     fn while_statement(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
         self.consume(
             TokenType::LeftParen,
             ParseError::MissingParenAfterWhile(self.previous()),
@@ -227,9 +501,17 @@ impl Parser {
             ParseError::MissingParenAfterWhileCondition(self.previous()),
         )?;
 
-        let body = Box::new(self.statement()?);
+        let body = Box::new(self.loop_body()?);
 
-        Ok(Stmt::While(condition, body))
+        Ok(Stmt::While(keyword, condition, body, None))
+    }
+
+    fn loop_body(&mut self) -> ParseResult<Stmt> {
+        self.loop_depth += 1;
+        let body = self.statement();
+        self.loop_depth -= 1;
+
+        body
     }
 
     fn if_statement(&mut self) -> ParseResult<Stmt> {
@@ -255,12 +537,13 @@ impl Parser {
     }
 
     fn print_statement(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
         let expr = self.expression()?;
         self.consume(
             TokenType::Semicolon,
             ParseError::MissingSemicolonAfterValue(self.previous()),
         )?;
-        Ok(Stmt::Print(expr))
+        Ok(Stmt::Print(keyword, expr))
     }
 
     fn block_statement(&mut self) -> ParseResult<Stmt> {
@@ -295,7 +578,7 @@ impl Parser {
         let expr = self.or()?;
 
         if self.do_match(vec![TokenType::Equal]) {
-            let equals = self.previous();
+            let _equals = self.previous();
             let value = self.assignment()?;
 
             if let Expr::Variable(name) = expr {
@@ -309,6 +592,18 @@ impl Parser {
                     name,
                     value: Box::new(value),
                 });
+            } else if let Expr::GetIndex {
+                object,
+                bracket,
+                index,
+            } = expr
+            {
+                return Ok(Expr::SetIndex {
+                    object,
+                    bracket,
+                    index,
+                    value: Box::new(value),
+                });
             }
         }
 
@@ -445,6 +740,18 @@ impl Parser {
                     object: Box::new(expr),
                     name,
                 };
+            } else if self.do_match(vec![TokenType::LeftBracket]) {
+                let bracket = self.previous();
+                let index = self.expression()?;
+                self.consume(
+                    TokenType::RightBracket,
+                    ParseError::MissingBracketAfterIndex(self.previous()),
+                )?;
+                expr = Expr::GetIndex {
+                    object: Box::new(expr),
+                    bracket,
+                    index: Box::new(index),
+                };
             } else {
                 break;
             }
@@ -488,6 +795,9 @@ impl Parser {
             TokenType::Number(number) => Ok(Expr::Literal(Literal::Number(number))),
             TokenType::String(string) => Ok(Expr::Literal(Literal::String(string))),
             TokenType::This => Ok(Expr::This(self.previous())),
+            TokenType::Fun => self.lambda(),
+            TokenType::LeftBracket => self.list(),
+            TokenType::LeftBrace => self.map(),
             TokenType::Identifier(_) => Ok(Expr::Variable(self.previous())),
             // XXX a '(' at the end causes a stack overflow
             TokenType::LeftParen if !self.is_at_end() => {
@@ -504,6 +814,55 @@ impl Parser {
         }
     }
 
+    fn list(&mut self) -> ParseResult<Expr> {
+        let mut elements = Vec::new();
+
+        if !self.check(&TokenType::RightBracket) {
+            loop {
+                elements.push(self.expression()?);
+
+                if !self.do_match(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume(
+            TokenType::RightBracket,
+            ParseError::MissingBracketAfterElements(self.previous()),
+        )?;
+
+        Ok(Expr::List { elements })
+    }
+
+    fn map(&mut self) -> ParseResult<Expr> {
+        let brace = self.previous();
+        let mut entries = Vec::new();
+
+        if !self.check(&TokenType::RightBrace) {
+            loop {
+                let key = self.expression()?;
+                self.consume(
+                    TokenType::Colon,
+                    ParseError::MissingColonAfterKey(self.previous()),
+                )?;
+                let value = self.expression()?;
+                entries.push((key, value));
+
+                if !self.do_match(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume(
+            TokenType::RightBrace,
+            ParseError::MissingBraceAfterEntries(self.previous()),
+        )?;
+
+        Ok(Expr::Map { brace, entries })
+    }
+
     fn do_match(&mut self, token_types: Vec<TokenType>) -> bool {
         for token_type in token_types {
             if self.check(&token_type) {
@@ -515,6 +874,7 @@ impl Parser {
         false
     }
 
+    #[allow(clippy::needless_pass_by_value)]
     fn consume(&mut self, token_type: TokenType, error: ParseError) -> ParseResult<Token> {
         if self.check(&token_type) {
             Ok(self.advance())
@@ -554,6 +914,11 @@ impl Parser {
         self.tokens[self.current].clone()
     }
 
+    // Returns the token at the given offset from the current token, or Eof if there is none
+    fn peek_at(&self, offset: usize) -> Token {
+        self.tokens[(self.current + offset).min(self.tokens.len() - 1)].clone()
+    }
+
     fn previous(&self) -> Token {
         self.tokens[self.current - 1].clone()
     }
@@ -677,6 +1042,303 @@ mod tests {
     }
 
     #[test]
+    fn parse_lambda() {
+        let result = parse("var f = fun (a) { return a; };").unwrap();
+        let a = token(TokenType::Identifier("a".to_string()));
+        let expected = Stmt::Var(
+            token(TokenType::Identifier("f".to_string())),
+            Some(Expr::Lambda {
+                keyword: token(TokenType::Fun),
+                params: vec![a.clone()],
+                body: vec![Stmt::Return(
+                    token(TokenType::Return),
+                    Some(Expr::Variable(a)),
+                )],
+            }),
+        );
+        assert_eq!(expected, result[0]);
+    }
+
+    #[test]
+    fn parse_lambda_with_expression_body() {
+        let result = parse("fun () => 1;").unwrap();
+        let expected = Stmt::Expr(Expr::Lambda {
+            keyword: token(TokenType::Fun),
+            params: vec![],
+            body: vec![Stmt::Return(
+                token(TokenType::Arrow),
+                Some(Expr::Literal(Literal::Number(1.0))),
+            )],
+        });
+        assert_eq!(expected, result[0]);
+    }
+
+    #[test]
+    fn parse_list() {
+        let result = parse("[1, true];").unwrap();
+        let expected = Stmt::Expr(Expr::List {
+            elements: vec![
+                Expr::Literal(Literal::Number(1.0)),
+                Expr::Literal(Literal::Bool(true)),
+            ],
+        });
+        assert_eq!(expected, result[0]);
+    }
+
+    #[test]
+    fn parse_map() {
+        let result = parse(r#"var m = {"a": 1, nil: true};"#).unwrap();
+        let expected = Stmt::Var(
+            token(TokenType::Identifier("m".to_string())),
+            Some(Expr::Map {
+                brace: token(TokenType::LeftBrace),
+                entries: vec![
+                    (
+                        Expr::Literal(Literal::String("a".to_string())),
+                        Expr::Literal(Literal::Number(1.0)),
+                    ),
+                    (
+                        Expr::Literal(Literal::Nil),
+                        Expr::Literal(Literal::Bool(true)),
+                    ),
+                ],
+            }),
+        );
+        assert_eq!(expected, result[0]);
+    }
+
+    #[test]
+    fn parse_index_getter_and_setter() {
+        let result = parse("xs[0]; xs[0] = 1;").unwrap();
+        let object = Box::new(Expr::Variable(token(TokenType::Identifier(
+            "xs".to_string(),
+        ))));
+        let index = Box::new(Expr::Literal(Literal::Number(0.0)));
+
+        let expected_read = Stmt::Expr(Expr::GetIndex {
+            object: object.clone(),
+            bracket: token(TokenType::LeftBracket),
+            index: index.clone(),
+        });
+        let expected_write = Stmt::Expr(Expr::SetIndex {
+            object,
+            bracket: token(TokenType::LeftBracket),
+            index,
+            value: Box::new(Expr::Literal(Literal::Number(1.0))),
+        });
+        assert_eq!(expected_read, result[0]);
+        assert_eq!(expected_write, result[1]);
+    }
+
+    #[test]
+    fn parse_break_and_continue() {
+        let result = parse("while (true) { break; continue; }").unwrap();
+        let expected = Stmt::While(
+            token(TokenType::While),
+            Expr::Literal(Literal::Bool(true)),
+            Box::new(Stmt::Block(vec![
+                Stmt::Break(token(TokenType::Break)),
+                Stmt::Continue(token(TokenType::Continue)),
+            ])),
+            None,
+        );
+        assert_eq!(expected, result[0]);
+    }
+
+    #[test]
+    fn parse_for_with_increment() {
+        let result = parse("for (;; i = 1) continue;").unwrap();
+        let expected = Stmt::While(
+            token(TokenType::For),
+            Expr::Literal(Literal::Bool(true)),
+            Box::new(Stmt::Continue(token(TokenType::Continue))),
+            Some(Expr::Assign {
+                name: token(TokenType::Identifier("i".to_string())),
+                value: Box::new(Expr::Literal(Literal::Number(1.0))),
+            }),
+        );
+        assert_eq!(expected, result[0]);
+    }
+
+    #[test]
+    fn parse_for_in() {
+        let result = parse("for (var x in xs) break;").unwrap();
+        let expected = Stmt::ForIn(
+            token(TokenType::Identifier("x".to_string())),
+            Expr::Variable(token(TokenType::Identifier("xs".to_string()))),
+            Box::new(Stmt::Break(token(TokenType::Break))),
+        );
+        assert_eq!(expected, result[0]);
+
+        let parse_errors = parse("for (var x in xs print x;").unwrap_err();
+        let expected_error =
+            ParseError::MissingParenAfterForClauses(token(TokenType::Identifier("xs".to_string())));
+        assert_eq!(expected_error, parse_errors[0]);
+    }
+
+    #[test]
+    fn parse_try_and_throw() {
+        let result = parse("try { throw 1; } catch (e) {} finally {}").unwrap();
+        let expected = Stmt::Try(
+            vec![Stmt::Throw(
+                token(TokenType::Throw),
+                Expr::Literal(Literal::Number(1.0)),
+            )],
+            Some((token(TokenType::Identifier("e".to_string())), Vec::new())),
+            Some(Vec::new()),
+        );
+        assert_eq!(expected, result[0]);
+
+        let codes_and_expected_errors = vec![
+            (
+                "try {}",
+                ParseError::MissingCatchOrFinally(token(TokenType::RightBrace)),
+            ),
+            (
+                "try {} catch e {}",
+                ParseError::MissingParenAfterCatch(token(TokenType::Catch)),
+            ),
+            (
+                "try {} catch (e {}",
+                ParseError::MissingParenAfterCatchVariable(token(TokenType::Identifier(
+                    "e".to_string(),
+                ))),
+            ),
+            (
+                "try {} finally;",
+                ParseError::MissingBraceBeforeBody(
+                    token(TokenType::Finally),
+                    "finally".to_string(),
+                ),
+            ),
+        ];
+
+        for (code, expected_error) in codes_and_expected_errors {
+            let parse_errors = parse(code).unwrap_err();
+            assert_eq!(expected_error, parse_errors[0]);
+        }
+    }
+
+    #[test]
+    fn parse_import() {
+        let result = parse("import \"a.lox\"; import \"b.lox\" as b;").unwrap();
+        assert_eq!(
+            Stmt::Import(token(TokenType::Import), "a.lox".to_string(), None),
+            result[0]
+        );
+        assert_eq!(
+            Stmt::Import(
+                token(TokenType::Import),
+                "b.lox".to_string(),
+                Some(token(TokenType::Identifier("b".to_string())))
+            ),
+            result[1]
+        );
+
+        let codes_and_expected_errors = vec![
+            (
+                "import a;",
+                ParseError::MissingModulePath(token(TokenType::Import)),
+            ),
+            (
+                "import \"a.lox\" as;",
+                ParseError::MissingName(token(TokenType::As), "module".to_string()),
+            ),
+            (
+                "import \"a.lox\"",
+                ParseError::MissingSemicolonAfterImport(token(TokenType::String(
+                    "a.lox".to_string(),
+                ))),
+            ),
+        ];
+
+        for (code, expected_error) in codes_and_expected_errors {
+            let parse_errors = parse(code).unwrap_err();
+            assert_eq!(expected_error, parse_errors[0]);
+        }
+    }
+
+    #[test]
+    fn parse_export_and_import_from() {
+        let result = parse("export var a; from \"m.lox\" import b, c;").unwrap();
+        assert_eq!(
+            Stmt::Export(
+                token(TokenType::Export),
+                Box::new(Stmt::Var(
+                    token(TokenType::Identifier("a".to_string())),
+                    None
+                ))
+            ),
+            result[0]
+        );
+        assert_eq!(
+            Stmt::ImportFrom(
+                token(TokenType::From),
+                "m.lox".to_string(),
+                vec![
+                    token(TokenType::Identifier("b".to_string())),
+                    token(TokenType::Identifier("c".to_string()))
+                ]
+            ),
+            result[1]
+        );
+
+        let codes_and_expected_errors = vec![
+            (
+                "export print 1;",
+                ParseError::MissingDeclarationAfterExport(token(TokenType::Export)),
+            ),
+            (
+                "{ export var a; }",
+                ParseError::ExportNotAtTopLevel(token(TokenType::Export)),
+            ),
+            (
+                "from m import a;",
+                ParseError::MissingModulePath(token(TokenType::From)),
+            ),
+            (
+                "from \"m.lox\" a;",
+                ParseError::MissingImportAfterModulePath(token(TokenType::String(
+                    "m.lox".to_string(),
+                ))),
+            ),
+            (
+                "from \"m.lox\" import a,;",
+                ParseError::MissingName(token(TokenType::Comma), "imported".to_string()),
+            ),
+        ];
+
+        for (code, expected_error) in codes_and_expected_errors {
+            let parse_errors = parse(code).unwrap_err();
+            assert_eq!(expected_error, parse_errors[0]);
+        }
+    }
+
+    #[test]
+    fn parse_invalid_loop_control_statements() {
+        let codes_and_expected_errors = vec![
+            (
+                "break;",
+                ParseError::KeywordOutsideLoop(token(TokenType::Break)),
+            ),
+            (
+                "while (true) { fun f() { continue; } }",
+                ParseError::KeywordOutsideLoop(token(TokenType::Continue)),
+            ),
+            (
+                "while (true) break",
+                ParseError::MissingSemicolonAfterKeyword(token(TokenType::Break)),
+            ),
+        ];
+
+        for (code, expected_error) in codes_and_expected_errors {
+            let parse_errors = parse(code).unwrap_err();
+            assert_eq!(vec![expected_error], parse_errors);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
     fn parse_invalid_statements() {
         let codes_and_expected_errors = vec![
             ("(", ParseError::InvalidToken(token(TokenType::LeftParen))),
@@ -716,6 +1378,26 @@ mod tests {
                     "function".to_string(),
                 ),
             ),
+            (
+                "[1, 2",
+                ParseError::MissingBracketAfterElements(token(TokenType::Number(2.0))),
+            ),
+            (
+                "var m = {1 2};",
+                ParseError::MissingColonAfterKey(token(TokenType::Number(1.0))),
+            ),
+            (
+                "var m = {1: 2",
+                ParseError::MissingBraceAfterEntries(token(TokenType::Number(2.0))),
+            ),
+            (
+                "xs[1",
+                ParseError::MissingBracketAfterIndex(token(TokenType::Number(1.0))),
+            ),
+            (
+                "var f = fun;",
+                ParseError::MissingParenAfterFun(token(TokenType::Fun)),
+            ),
             (
                 "fun xyz(",
                 ParseError::MissingParameterName(token(TokenType::LeftParen)),
@@ -770,6 +1452,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_single_expression() {
+        let result = parse_expression("a > 100").unwrap();
+        let expected = Expr::Binary {
+            left: Box::new(Expr::Variable(token(TokenType::Identifier(
+                "a".to_string(),
+            )))),
+            operator: token(TokenType::Greater),
+            right: Box::new(Expr::Literal(Literal::Number(100.0))),
+        };
+        assert_eq!(expected, result);
+
+        assert_eq!(
+            Err(vec![ParseError::UnexpectedTokenAfterExpression(token(
+                TokenType::Semicolon
+            ))]),
+            parse_expression("a > 100;")
+        );
+        assert!(parse_expression("var a = 1").is_err());
+        assert_eq!(
+            Err(vec![ParseError::MissingExpression(token(TokenType::Eof))]),
+            parse_expression("")
+        );
+        assert_eq!(
+            Err(vec![ParseError::KeywordOutsideLoop(token(
+                TokenType::Break
+            ))]),
+            parse_expression("fun () { break; }")
+        );
+    }
+
+    fn parse_expression(code: &str) -> Result<Expr, Vec<ParseError>> {
+        Parser::new().parse_expression(Scanner::scan(code).unwrap())
+    }
+
     fn parse(code: &str) -> Result<Vec<Stmt>, Vec<ParseError>> {
         Parser::new().parse(Scanner::scan(code).unwrap())
     }
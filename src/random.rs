@@ -0,0 +1,66 @@
+use std::time::SystemTime;
+
+// A pseudorandom number generator (SplitMix64), the same seed always produces the same numbers.
+// It's fast and good enough for scripts, but not suitable for cryptography.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    // Seeds the generator from the system clock, so every run gets different numbers
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos());
+
+        // only the low bits change between runs
+        #[allow(clippy::cast_possible_truncation)]
+        Self::new(nanos as u64)
+    }
+
+    pub const fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Returns a number in [0, 1), using the upper 53 bits as f64 has a 53-bit mantissa
+    #[allow(clippy::cast_precision_loss)]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+
+        let numbers: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        assert_eq!(numbers, (0..5).map(|_| b.next_u64()).collect::<Vec<u64>>());
+        assert_ne!(numbers, (0..5).map(|_| c.next_u64()).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn numbers_between_zero_and_one() {
+        let mut rng = Rng::new(0);
+
+        for _ in 0..1000 {
+            let number = rng.next_f64();
+            assert!((0.0..1.0).contains(&number));
+        }
+    }
+}
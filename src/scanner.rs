@@ -7,17 +7,29 @@ type ScanResult<T> = Result<T, ScanError>;
 pub struct Scanner {}
 
 impl Scanner {
+    /// Splits the source into tokens, the last token is always `Eof`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first invalid token, e.g. an unterminated string.
     pub fn scan(source: &str) -> ScanResult<Vec<Token>> {
         let tokens = vec![];
         let initial_line = 1;
-        Self::scan_token(source, tokens, initial_line)
+        Self::scan_tokens(source, tokens, initial_line)
     }
 
-    fn scan_token(source: &str, mut tokens: Vec<Token>, mut line: usize) -> ScanResult<Vec<Token>> {
-        if source.is_empty() {
-            tokens.push(Token::new(TokenType::Eof, line));
-            Ok(tokens)
-        } else {
+    fn scan_tokens(
+        mut source: &str,
+        mut tokens: Vec<Token>,
+        mut line: usize,
+    ) -> ScanResult<Vec<Token>> {
+        // a loop rather than recursion, so that long programs don't overflow the stack
+        loop {
+            if source.is_empty() {
+                tokens.push(Token::new(TokenType::Eof, line));
+                return Ok(tokens);
+            }
+
             let mut munched_chars = 1;
             let c = source.chars().next().unwrap();
 
@@ -26,6 +38,9 @@ impl Scanner {
                 ')' => Some(Token::new(TokenType::RightParen, line)),
                 '{' => Some(Token::new(TokenType::LeftBrace, line)),
                 '}' => Some(Token::new(TokenType::RightBrace, line)),
+                '[' => Some(Token::new(TokenType::LeftBracket, line)),
+                ']' => Some(Token::new(TokenType::RightBracket, line)),
+                ':' => Some(Token::new(TokenType::Colon, line)),
                 ',' => Some(Token::new(TokenType::Comma, line)),
                 '.' => Some(Token::new(TokenType::Dot, line)),
                 '-' => Some(Token::new(TokenType::Minus, line)),
@@ -33,12 +48,7 @@ impl Scanner {
                 ';' => Some(Token::new(TokenType::Semicolon, line)),
                 '*' => Some(Token::new(TokenType::Star, line)),
                 '/' if matches!(source.chars().nth(1), Some('/')) => {
-                    let linebreak_position = source.find('\n');
-                    if linebreak_position == None {
-                        munched_chars = source.len();
-                    } else {
-                        munched_chars = linebreak_position.unwrap();
-                    }
+                    munched_chars = source.find('\n').unwrap_or(source.len());
                     None
                 }
                 '/' => Some(Token::new(TokenType::Slash, line)),
@@ -49,6 +59,9 @@ impl Scanner {
                 '=' if matches!(source.chars().nth(1), Some('=')) => {
                     Some(Token::new(TokenType::EqualEqual, line))
                 }
+                '=' if matches!(source.chars().nth(1), Some('>')) => {
+                    Some(Token::new(TokenType::Arrow, line))
+                }
                 '=' => Some(Token::new(TokenType::Equal, line)),
                 '<' if matches!(source.chars().nth(1), Some('=')) => {
                     Some(Token::new(TokenType::LessEqual, line))
@@ -78,7 +91,7 @@ impl Scanner {
                 tokens.push(token);
             }
 
-            Self::scan_token(&source[munched_chars..], tokens, line)
+            source = &source[munched_chars..];
         }
     }
 
@@ -88,10 +101,8 @@ impl Scanner {
             .take_while(|c| c.is_ascii_alphabetic() || *c == '_')
             .collect();
 
-        let token_type = match Self::get_type_if_keyword(&identifier) {
-            Some(keyword_type) => keyword_type,
-            None => TokenType::Identifier(identifier),
-        };
+        let token_type =
+            Self::get_type_if_keyword(&identifier).unwrap_or(TokenType::Identifier(identifier));
 
         Token::new(token_type, line)
     }
@@ -141,19 +152,30 @@ impl Scanner {
     fn get_type_if_keyword(keyword: &str) -> Option<TokenType> {
         match keyword {
             "and" => Some(TokenType::And),
+            "as" => Some(TokenType::As),
+            "break" => Some(TokenType::Break),
+            "catch" => Some(TokenType::Catch),
             "class" => Some(TokenType::Class),
+            "continue" => Some(TokenType::Continue),
             "else" => Some(TokenType::Else),
+            "export" => Some(TokenType::Export),
             "false" => Some(TokenType::False),
+            "finally" => Some(TokenType::Finally),
             "fun" => Some(TokenType::Fun),
             "for" => Some(TokenType::For),
+            "from" => Some(TokenType::From),
             "if" => Some(TokenType::If),
+            "import" => Some(TokenType::Import),
+            "in" => Some(TokenType::In),
             "nil" => Some(TokenType::Nil),
             "or" => Some(TokenType::Or),
             "print" => Some(TokenType::Print),
             "return" => Some(TokenType::Return),
             "super" => Some(TokenType::Super),
             "this" => Some(TokenType::This),
+            "throw" => Some(TokenType::Throw),
             "true" => Some(TokenType::True),
+            "try" => Some(TokenType::Try),
             "var" => Some(TokenType::Var),
             "while" => Some(TokenType::While),
             _ => None,
@@ -180,6 +202,9 @@ mod tests {
             (")", TokenType::RightParen),
             ("{", TokenType::LeftBrace),
             ("}", TokenType::RightBrace),
+            ("[", TokenType::LeftBracket),
+            ("]", TokenType::RightBracket),
+            (":", TokenType::Colon),
             (",", TokenType::Comma),
             (".", TokenType::Dot),
             ("-", TokenType::Minus),
@@ -206,6 +231,7 @@ mod tests {
             (">", TokenType::Greater),
             ("!=", TokenType::BangEqual),
             ("==", TokenType::EqualEqual),
+            ("=>", TokenType::Arrow),
             ("<=", TokenType::LessEqual),
             (">=", TokenType::GreaterEqual),
         ];
@@ -264,8 +290,8 @@ mod tests {
     #[test]
     fn scan_unterminated_string() {
         match Scanner::scan("\"A string") {
-            Err(ScanError::UnterminatedString(_)) => assert!(true),
-            _ => assert!(false),
+            Err(ScanError::UnterminatedString(_)) => {}
+            _ => panic!("unexpected error"),
         }
     }
 
@@ -283,7 +309,7 @@ mod tests {
 
     #[test]
     fn scan_number_literals() {
-        let numbers_and_literals = vec![("123", 123 as f64), ("123.45", 123.45), ("123.0", 123.0)];
+        let numbers_and_literals = vec![("123", 123_f64), ("123.45", 123.45), ("123.0", 123.0)];
 
         for (number, literal) in numbers_and_literals {
             let result = Scanner::scan(number).unwrap();
@@ -297,8 +323,8 @@ mod tests {
     #[test]
     fn scan_invalid_number() {
         match Scanner::scan("123.") {
-            Err(ScanError::NumberEndsWithDot(_)) => assert!(true),
-            _ => assert!(false),
+            Err(ScanError::NumberEndsWithDot(_)) => {}
+            _ => panic!("unexpected error"),
         }
     }
 
@@ -321,19 +347,30 @@ mod tests {
     fn scan_keywords() {
         let keywords_and_token_types = vec![
             ("and", TokenType::And),
+            ("as", TokenType::As),
+            ("break", TokenType::Break),
+            ("catch", TokenType::Catch),
             ("class", TokenType::Class),
+            ("continue", TokenType::Continue),
             ("else", TokenType::Else),
+            ("export", TokenType::Export),
             ("false", TokenType::False),
+            ("finally", TokenType::Finally),
             ("fun", TokenType::Fun),
             ("for", TokenType::For),
+            ("from", TokenType::From),
             ("if", TokenType::If),
+            ("import", TokenType::Import),
+            ("in", TokenType::In),
             ("nil", TokenType::Nil),
             ("or", TokenType::Or),
             ("print", TokenType::Print),
             ("return", TokenType::Return),
             ("super", TokenType::Super),
             ("this", TokenType::This),
+            ("throw", TokenType::Throw),
             ("true", TokenType::True),
+            ("try", TokenType::Try),
             ("var", TokenType::Var),
             ("while", TokenType::While),
         ];
@@ -352,8 +389,8 @@ mod tests {
 
         for invalid_char in invalid_chars {
             match Scanner::scan(invalid_char) {
-                Err(ScanError::UnexpectedChar(_, _)) => assert!(true),
-                _ => assert!(false),
+                Err(ScanError::UnexpectedChar(_, _)) => {}
+                _ => panic!("unexpected error"),
             }
         }
     }
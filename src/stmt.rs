@@ -3,13 +3,26 @@ use crate::token::Token;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Stmt {
-    Block(Vec<Stmt>),
-    Class(Token, Vec<Stmt>),
+    Block(Vec<Self>),
+    Break(Token),
+    Class(Token, Vec<Self>),
+    Continue(Token),
+    // a top-level declaration that is visible to modules importing this one
+    Export(Token, Box<Self>),
     Expr(Expr),
-    Function(Token, Vec<Token>, Vec<Stmt>),
-    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
-    Print(Expr),
+    ForIn(Token, Expr, Box<Self>),
+    Function(Token, Vec<Token>, Vec<Self>),
+    If(Expr, Box<Self>, Option<Box<Self>>),
+    // the path of the module and the name it is bound to, if it differs from the file name
+    Import(Token, String, Option<Token>),
+    // the path of the module and the names that are imported from it
+    ImportFrom(Token, String, Vec<Token>),
+    Print(Token, Expr),
     Return(Token, Option<Expr>),
+    Throw(Token, Expr),
+    // the body, the optional 'catch' clause with its variable, and the optional 'finally' clause
+    Try(Vec<Self>, Option<(Token, Vec<Self>)>, Option<Vec<Self>>),
     Var(Token, Option<Expr>),
-    While(Expr, Box<Stmt>),
+    // the optional expression is the increment clause of a desugared 'for' loop
+    While(Token, Expr, Box<Self>, Option<Expr>),
 }
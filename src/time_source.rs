@@ -0,0 +1,30 @@
+use std::time::SystemTime;
+
+// Provides the current time to scripts, hosts can replace it, e.g. to make runs reproducible
+pub trait TimeSource {
+    // Returns the seconds since 1970-01-01
+    fn now(&self) -> f64;
+}
+
+// The time of the system clock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemTimeSource;
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> f64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs_f64()
+    }
+}
+
+// A clock that is frozen at the given time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FixedTimeSource(pub f64);
+
+impl TimeSource for FixedTimeSource {
+    fn now(&self) -> f64 {
+        self.0
+    }
+}
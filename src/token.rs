@@ -1,5 +1,6 @@
 use crate::token_type::TokenType;
 
+#[allow(clippy::struct_field_names)]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Token {
     pub token_type: TokenType,
@@ -8,15 +9,17 @@ pub struct Token {
 }
 
 impl Token {
+    #[must_use]
     pub fn new(token_type: TokenType, line: usize) -> Self {
         let lexeme = match token_type {
-            TokenType::String(_) => format!("\"{}\"", token_type.to_string()),
+            TokenType::String(_) => format!("\"{token_type}\""),
             _ => token_type.to_string(),
         };
 
         Self::new_with_lexeme(token_type, lexeme, line)
     }
 
+    #[must_use]
     pub const fn new_with_lexeme(token_type: TokenType, lexeme: String, line: usize) -> Self {
         Self {
             token_type,
@@ -7,6 +7,9 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
+    Colon,
     Comma,
     Dot,
     Minus,
@@ -16,6 +19,7 @@ pub enum TokenType {
     Star,
 
     // One or two character tokens
+    Arrow,
     Bang,
     BangEqual,
     Equal,
@@ -32,19 +36,30 @@ pub enum TokenType {
 
     // Keywords
     And,
+    As,
+    Break,
+    Catch,
     Class,
+    Continue,
     Else,
+    Export,
     False,
+    Finally,
     Fun,
     For,
+    From,
     If,
+    Import,
+    In,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
 
@@ -58,6 +73,9 @@ impl fmt::Display for TokenType {
             Self::RightParen => write!(f, ")"),
             Self::LeftBrace => write!(f, "{{"), // https://doc.rust-lang.org/std/fmt/index.html#escaping
             Self::RightBrace => write!(f, "}}"), // https://doc.rust-lang.org/std/fmt/index.html#escaping
+            Self::LeftBracket => write!(f, "["),
+            Self::RightBracket => write!(f, "]"),
+            Self::Colon => write!(f, ":"),
             Self::Comma => write!(f, ","),
             Self::Dot => write!(f, "."),
             Self::Minus => write!(f, "-"),
@@ -65,6 +83,7 @@ impl fmt::Display for TokenType {
             Self::Semicolon => write!(f, ";"),
             Self::Slash => write!(f, "/"),
             Self::Star => write!(f, "*"),
+            Self::Arrow => write!(f, "=>"),
             Self::Bang => write!(f, "!"),
             Self::BangEqual => write!(f, "!="),
             Self::Equal => write!(f, "="),
@@ -73,23 +92,34 @@ impl fmt::Display for TokenType {
             Self::GreaterEqual => write!(f, ">="),
             Self::Less => write!(f, "<"),
             Self::LessEqual => write!(f, "<="),
-            Self::Identifier(id) => write!(f, "{}", id),
-            Self::String(s) => write!(f, "{}", s),
-            Self::Number(n) => write!(f, "{}", n),
+            Self::Identifier(id) => write!(f, "{id}"),
+            Self::String(s) => write!(f, "{s}"),
+            Self::Number(n) => write!(f, "{n}"),
             Self::And => write!(f, "and"),
+            Self::As => write!(f, "as"),
+            Self::Break => write!(f, "break"),
+            Self::Catch => write!(f, "catch"),
             Self::Class => write!(f, "class"),
+            Self::Continue => write!(f, "continue"),
             Self::Else => write!(f, "else"),
+            Self::Export => write!(f, "export"),
             Self::False => write!(f, "false"),
+            Self::Finally => write!(f, "finally"),
             Self::Fun => write!(f, "fun"),
             Self::For => write!(f, "for"),
+            Self::From => write!(f, "from"),
             Self::If => write!(f, "if"),
+            Self::Import => write!(f, "import"),
+            Self::In => write!(f, "in"),
             Self::Nil => write!(f, "nil"),
             Self::Or => write!(f, "or"),
             Self::Print => write!(f, "print"),
             Self::Return => write!(f, "return"),
             Self::Super => write!(f, "super"),
             Self::This => write!(f, "this"),
+            Self::Throw => write!(f, "throw"),
             Self::True => write!(f, "true"),
+            Self::Try => write!(f, "try"),
             Self::Var => write!(f, "var"),
             Self::While => write!(f, "while"),
             Self::Eof => write!(f, "eof"),
@@ -1,37 +1,191 @@
+use std::cell::RefCell;
 use std::fmt;
+use std::rc::Rc;
 
 use crate::lox_callable::LoxCallable;
+use crate::lox_class::LoxClass;
+use crate::lox_instance::LoxInstance;
+use crate::lox_map::LoxMap;
+use crate::lox_module::LoxModule;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub enum Value {
     Bool(bool),
+    Class(Rc<LoxClass>),
     Function(Box<dyn LoxCallable>),
+    Instance(Rc<RefCell<LoxInstance>>),
+    List(Rc<RefCell<Vec<Self>>>),
+    Map(Rc<RefCell<LoxMap>>),
+    Module(Rc<LoxModule>),
     Nil,
     Number(f64),
     String(String),
 }
 
+// Lox equality, which is also used by the host: numbers, strings, bools and nil compare by value,
+// everything else has reference semantics and is only equal to itself
+impl PartialEq for Value {
+    #[allow(clippy::float_cmp)]
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Bool(l), Self::Bool(r)) => l == r,
+            (Self::Class(l), Self::Class(r)) => Rc::ptr_eq(l, r),
+            (Self::Function(l), Self::Function(r)) => l == r,
+            (Self::Instance(l), Self::Instance(r)) => Rc::ptr_eq(l, r),
+            (Self::List(l), Self::List(r)) => Rc::ptr_eq(l, r),
+            (Self::Map(l), Self::Map(r)) => Rc::ptr_eq(l, r),
+            (Self::Module(l), Self::Module(r)) => Rc::ptr_eq(l, r),
+            (Self::Nil, Self::Nil) => true,
+            (Self::Number(l), Self::Number(r)) => l == r,
+            (Self::String(l), Self::String(r)) => l == r,
+            _ => false,
+        }
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Bool(bool) => write!(f, "{}", bool),
-            Self::Function(function) => write!(f, "{:?}", function), // XXX what should the output be?
+            Self::Bool(bool) => write!(f, "{bool}"),
+            Self::Class(class) => write!(f, "{class}"),
+            Self::Function(function) => write!(f, "{function}"),
+            Self::Instance(instance) => write!(f, "{}", instance.borrow()),
+            Self::List(list) => {
+                let Some(_guard) = DisplayGuard::enter(Rc::as_ptr(list).cast()) else {
+                    return write!(f, "[...]");
+                };
+                let elements: Vec<String> = list.borrow().iter().map(ToString::to_string).collect();
+                write!(f, "[{}]", elements.join(", "))
+            }
+            Self::Map(map) => {
+                let Some(_guard) = DisplayGuard::enter(Rc::as_ptr(map).cast()) else {
+                    return write!(f, "{{...}}");
+                };
+                let entries: Vec<String> = map
+                    .borrow()
+                    .iter()
+                    .map(|(key, value)| format!("{}: {value}", key.to_value()))
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }
+            Self::Module(module) => write!(f, "{module}"),
             Self::Nil => write!(f, "nil"),
-            Self::Number(number) => write!(f, "{}", number),
-            Self::String(string) => write!(f, "{}", string),
+            Self::Number(number) => write!(f, "{}", format_number(*number)),
+            Self::String(string) => write!(f, "{string}"),
         }
     }
 }
 
+thread_local! {
+    // The lists and maps that are currently being displayed, to stop at cycles
+    static DISPLAYING: RefCell<Vec<*const ()>> = const { RefCell::new(Vec::new()) };
+}
+
+// Marks a list or map as being displayed until it is dropped
+struct DisplayGuard(*const ());
+
+impl DisplayGuard {
+    // Returns None if the list or map is already being displayed further up, i.e. contains itself
+    fn enter(container: *const ()) -> Option<Self> {
+        DISPLAYING.with_borrow_mut(|displaying| {
+            if displaying.contains(&container) {
+                None
+            } else {
+                displaying.push(container);
+                Some(Self(container))
+            }
+        })
+    }
+}
+
+impl Drop for DisplayGuard {
+    fn drop(&mut self) {
+        DISPLAYING.with_borrow_mut(|displaying| displaying.retain(|c| *c != self.0));
+    }
+}
+
 impl Value {
+    #[must_use]
     pub const fn is_truthy(&self) -> bool {
         !matches!(self, Self::Nil | Self::Bool(false))
     }
+
+    #[must_use]
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "bool",
+            Self::Class(_) => "class",
+            Self::Function(_) => "function",
+            Self::Instance(_) => "instance",
+            Self::List(_) => "list",
+            Self::Map(_) => "map",
+            Self::Module(_) => "module",
+            Self::Nil => "nil",
+            Self::Number(_) => "number",
+            Self::String(_) => "string",
+        }
+    }
+}
+
+// The number of significant digits of formatted numbers
+const PRECISION: i32 = 6;
+
+// Formats a number like clox does with printf's "%g": six significant digits, trailing zeros
+// removed, and scientific notation for exponents below -4 or of at least six.
+fn format_number(number: f64) -> String {
+    if number.is_nan() {
+        // printf keeps the sign of NaN too, and 0.0 / 0.0 is negative on x86-64
+        return if number.is_sign_negative() {
+            "-nan"
+        } else {
+            "nan"
+        }
+        .to_string();
+    }
+    if number.is_infinite() {
+        return if number > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    if number == 0.0 {
+        // like printf, the sign of negative zero is kept
+        return if number.is_sign_negative() { "-0" } else { "0" }.to_string();
+    }
+
+    // rounding to the precision may change the exponent, e.g. 999999.5 becomes 1.00000e6
+    let scientific = format!("{:.*e}", (PRECISION - 1) as usize, number);
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation has an exponent");
+    let exponent: i32 = exponent.parse().expect("the exponent is an integer");
+
+    if (-4..PRECISION).contains(&exponent) {
+        #[allow(clippy::cast_sign_loss)]
+        let decimals = (PRECISION - 1 - exponent) as usize;
+        trim_fraction(&format!("{number:.decimals$}")).to_string()
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!(
+            "{}e{sign}{:02}",
+            trim_fraction(mantissa),
+            exponent.unsigned_abs()
+        )
+    }
+}
+
+// Removes trailing zeros after the decimal point, and the decimal point if nothing follows it
+fn trim_fraction(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Value;
+    use crate::lox_map::{LoxMap, MapKey};
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     #[test]
     fn display() {
@@ -40,14 +194,118 @@ mod tests {
         assert_eq!("true", format!("{}", Value::Bool(true)));
         assert_eq!("false", format!("{}", Value::Bool(false)));
         assert_eq!("nil", format!("{}", Value::Nil));
+
+        let list = vec![Value::Number(1.0), Value::String("a".to_string())];
+        assert_eq!(
+            "[1, a]",
+            format!("{}", Value::List(Rc::new(RefCell::new(list))))
+        );
+
+        let mut map = LoxMap::new();
+        map.insert(MapKey::String("a".to_string()), Value::Number(1.0));
+        map.insert(MapKey::Bool(true), Value::Nil);
+        assert_eq!(
+            "{a: 1, true: nil}",
+            format!("{}", Value::Map(Rc::new(RefCell::new(map))))
+        );
+    }
+
+    #[test]
+    fn equality() {
+        assert_eq!(Value::Number(1.0), Value::Number(1.0));
+        assert_eq!(
+            Value::String("a".to_string()),
+            Value::String("a".to_string())
+        );
+        assert_ne!(Value::Nil, Value::Bool(false));
+
+        // lists and maps are only equal to themselves, even if they contain themselves
+        let list = Rc::new(RefCell::new(vec![]));
+        list.borrow_mut().push(Value::List(Rc::clone(&list)));
+        assert_eq!(Value::List(Rc::clone(&list)), Value::List(Rc::clone(&list)));
+        assert_ne!(
+            Value::List(Rc::new(RefCell::new(vec![]))),
+            Value::List(Rc::new(RefCell::new(vec![])))
+        );
+        assert_ne!(
+            Value::Map(Rc::new(RefCell::new(LoxMap::new()))),
+            Value::Map(Rc::new(RefCell::new(LoxMap::new())))
+        );
+
+        list.borrow_mut().clear();
+    }
+
+    #[test]
+    fn display_cycles() {
+        let list = Rc::new(RefCell::new(vec![Value::Number(1.0)]));
+        list.borrow_mut().push(Value::List(Rc::clone(&list)));
+        assert_eq!("[1, [...]]", Value::List(Rc::clone(&list)).to_string());
+
+        let map = Rc::new(RefCell::new(LoxMap::new()));
+        let map_value = Value::Map(Rc::clone(&map));
+        map.borrow_mut()
+            .insert(MapKey::String("self".to_string()), map_value.clone());
+        map.borrow_mut().insert(
+            MapKey::String("list".to_string()),
+            Value::List(Rc::clone(&list)),
+        );
+        assert_eq!("{self: {...}, list: [1, [...]]}", map_value.to_string());
+
+        // the same list twice is not a cycle
+        let inner = Value::List(Rc::new(RefCell::new(vec![])));
+        let outer = Value::List(Rc::new(RefCell::new(vec![inner.clone(), inner])));
+        assert_eq!("[[], []]", outer.to_string());
+
+        // break the cycles so the test doesn't leak
+        list.borrow_mut().clear();
+        map.borrow_mut().remove(&MapKey::String("self".to_string()));
+    }
+
+    #[test]
+    fn display_numbers() {
+        // computed at runtime like in Lox code, where x86-64 produces a negative NaN
+        let zero = std::hint::black_box(0.0_f64);
+        let nan_of_division = if cfg!(target_arch = "x86_64") {
+            "-nan"
+        } else {
+            "nan"
+        };
+
+        // the expected strings are the output of clox for the same numbers
+        let numbers_and_expectations = vec![
+            (0.0, "0"),
+            (-0.0, "-0"),
+            (1.0, "1"),
+            (-42.0, "-42"),
+            (123_456.0, "123456"),
+            (1_234_567.0, "1.23457e+06"),
+            (999_999.5, "1e+06"),
+            (1e21, "1e+21"),
+            (0.1 + 0.2, "0.3"),
+            (2.5, "2.5"),
+            (1.0 / 3.0, "0.333333"),
+            (0.0001, "0.0001"),
+            (0.000_012_5, "1.25e-05"),
+            (1e-300, "1e-300"),
+            (std::f64::consts::PI, "3.14159"),
+            (f64::NAN, "nan"),
+            (-f64::NAN, "-nan"),
+            (zero / zero, nan_of_division),
+            (f64::INFINITY, "inf"),
+            (f64::NEG_INFINITY, "-inf"),
+        ];
+
+        for (number, expected) in numbers_and_expectations {
+            assert_eq!(expected, Value::Number(number).to_string(), "{number:e}");
+        }
     }
 
     #[test]
     fn is_truthy() {
-        assert_eq!(false, Value::Nil.is_truthy());
-        assert_eq!(false, Value::Bool(false).is_truthy());
-        assert_eq!(true, Value::Bool(true).is_truthy());
-        assert_eq!(true, Value::Number(0.0).is_truthy());
-        assert_eq!(true, Value::String("".to_string()).is_truthy());
+        assert!(!Value::Nil.is_truthy());
+        assert!(!Value::Bool(false).is_truthy());
+        assert!(Value::Bool(true).is_truthy());
+        assert!(Value::Number(0.0).is_truthy());
+        assert!(Value::String(String::new()).is_truthy());
     }
 }
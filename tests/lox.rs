@@ -0,0 +1,320 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use rlox::{Capability, Limits, Lox, LoxError, RuntimeError, TimeSource, Value};
+
+#[test]
+fn run_returns_value_of_last_expression() {
+    let mut lox = Lox::new();
+
+    assert_eq!(Value::Number(3.0), lox.run("1 + 2;").unwrap());
+    assert_eq!(Value::Nil, lox.run("var a = 1;").unwrap());
+}
+
+#[test]
+fn state_is_kept_between_runs() {
+    let mut lox = Lox::new();
+    lox.run("fun add(a, b) { return a + b; } var x = 40;")
+        .unwrap();
+
+    assert_eq!(Value::Number(42.0), lox.run("add(x, 2);").unwrap());
+}
+
+#[test]
+fn errors_of_all_phases() {
+    let mut lox = Lox::new();
+
+    assert!(matches!(lox.run("\"unterminated"), Err(LoxError::Scan(_))));
+    assert!(matches!(lox.run("print ;"), Err(LoxError::Parse(errors)) if errors.len() == 1));
+    assert!(matches!(
+        lox.run("undefined;"),
+        Err(LoxError::Runtime(RuntimeError::UndefinedVariable(_)))
+    ));
+}
+
+#[test]
+fn run_file_resolves_imports_relative_to_the_file() {
+    let dir = std::env::temp_dir().join(format!("rlox-api-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("lib.lox"), "export var answer = 42;").unwrap();
+    std::fs::write(
+        dir.join("main.lox"),
+        "from \"lib.lox\" import answer;\nanswer;",
+    )
+    .unwrap();
+
+    let result = Lox::new().run_file(&dir.join("main.lox"));
+
+    assert_eq!(Value::Number(42.0), result.unwrap());
+    assert!(matches!(
+        Lox::new().run_file(&dir.join("missing.lox")),
+        Err(LoxError::Io(_))
+    ));
+}
+
+#[test]
+fn host_functions_and_globals() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut lox = Lox::new();
+
+    let captured_log = Rc::clone(&log);
+    lox.interpreter_mut().register_fn("log", 1, move |args| {
+        captured_log.borrow_mut().push(args[0].to_string());
+        Ok::<Value, String>(Value::Nil)
+    });
+    lox.interpreter_mut()
+        .register_fn("fail", 0, |_| Err("host failure"));
+    lox.interpreter_mut()
+        .define_global("answer", Value::Number(42.0));
+
+    lox.run("log(answer); log(\"done\");").unwrap();
+    assert_eq!(vec!["42".to_string(), "done".to_string()], *log.borrow());
+
+    match lox.run("\nfail();") {
+        Err(LoxError::Runtime(RuntimeError::HostError(name, message, 2))) => {
+            assert_eq!(("fail", "host failure"), (name.as_str(), message.as_str()));
+        }
+        _ => panic!("unexpected result"),
+    }
+    let caught = lox.run("var m; try { fail(); } catch (e) { m = e.message; } m;");
+    assert_eq!(
+        Value::String("fail: host failure".to_string()),
+        caught.unwrap()
+    );
+}
+
+#[test]
+fn typed_host_functions() {
+    fn longer_than(limit: f64, text: String) -> bool {
+        text.len() as f64 > limit
+    }
+
+    let mut lox = Lox::new();
+    lox.interpreter_mut()
+        .register_typed_fn("longer_than", longer_than);
+    lox.interpreter_mut()
+        .register_typed_fn("words", |text: String| -> Vec<String> {
+            text.split_whitespace().map(str::to_string).collect()
+        });
+    lox.interpreter_mut()
+        .register_typed_fn("pad", |value: Value, width: u32| {
+            format!("{:>width$}", value.to_string(), width = width as usize)
+        });
+    lox.interpreter_mut()
+        .define_global("limits", HashMap::from([("max".to_string(), 3)]).into());
+
+    assert_eq!(
+        Value::Bool(true),
+        lox.run("longer_than(limits[\"max\"], \"four\");").unwrap()
+    );
+    assert_eq!(Value::from("  [1]"), lox.run("pad([1], 5);").unwrap());
+    let words = lox.run("words(\"a b\");").unwrap();
+    assert_eq!(Ok(vec!["a".to_string(), "b".to_string()]), words.try_into());
+
+    match lox.run("longer_than(\"1\", \"a\");") {
+        Err(LoxError::Runtime(RuntimeError::HostError(_, message, 1))) => {
+            assert_eq!("Expected a number but got string \"1\"", message);
+        }
+        _ => panic!("unexpected result"),
+    }
+    match lox.run("pad(1, -1);") {
+        Err(LoxError::Runtime(RuntimeError::HostError(_, message, 1))) => {
+            assert_eq!(
+                "Expected an integer in the range of u32 but got number '-1'",
+                message
+            );
+        }
+        _ => panic!("unexpected result"),
+    }
+}
+
+#[test]
+fn capture_output() {
+    #[derive(Clone, Default)]
+    struct Output(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let output = Output::default();
+    let mut lox = Lox::new();
+    lox.interpreter_mut().set_output(output.clone());
+
+    lox.run("for (var i = 0; i < 3; i = i + 1) print i;")
+        .unwrap();
+    lox.run("print [1, \"two\"];").unwrap();
+    assert_eq!(
+        "0\n1\n2\n[1, two]\n",
+        String::from_utf8(output.0.take()).unwrap()
+    );
+}
+
+#[test]
+fn cancel_from_another_thread() {
+    let mut lox = Lox::new();
+    let handle = lox.interpreter().cancel_handle();
+
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        handle.cancel();
+    });
+
+    match lox.run("var i = 0;\nwhile (true) { i = i + 1; }") {
+        Err(LoxError::Runtime(RuntimeError::Cancelled(2))) => {}
+        _ => panic!("unexpected result"),
+    }
+    canceller.join().unwrap();
+
+    lox.interpreter_mut().set_limits(Limits {
+        max_steps: Some(1000),
+        ..Limits::default()
+    });
+    assert!(matches!(
+        lox.run("fun loop() { while (true) {} } loop();"),
+        Err(LoxError::Runtime(RuntimeError::StepLimitExceeded(1000, 1)))
+    ));
+}
+
+#[test]
+fn deep_recursion_with_default_limits() {
+    // the default stack of threads spawned by std
+    let recursion = thread::Builder::new()
+        .stack_size(2 * 1024 * 1024)
+        .spawn(|| {
+            let mut lox = Lox::new();
+            let programs = [
+                "fun f(n) { return f(n + 1) + 1; }\nf(0);".to_string(),
+                "class A { init(n) { A(n + 1); } }\nA(0);".to_string(),
+                "fun f(n) { var l = [n]; for (var x in l) { if (true) { return f(x + 1); } } }\nf(0);"
+                    .to_string(),
+                // parsed without recursion, but evaluated with it
+                format!("1{};", " + 1".repeat(10_000)),
+            ];
+
+            for program in &programs {
+                match lox.run(program) {
+                    Err(LoxError::Runtime(
+                        RuntimeError::StackLimitExceeded(..) | RuntimeError::CallDepthExceeded(..),
+                    )) => {}
+                    _ => panic!("unexpected result"),
+                }
+            }
+        });
+
+    assert!(recursion.unwrap().join().is_ok());
+}
+
+#[test]
+fn restrict_capabilities() {
+    let mut lox = Lox::new();
+    lox.interpreter_mut()
+        .set_capabilities(&[Capability::Time, Capability::Env]);
+
+    assert_eq!(Value::Bool(true), lox.run("clock() > 0;").unwrap());
+    match lox.run("args();") {
+        Err(LoxError::Runtime(RuntimeError::CapabilityDenied(name, capability, 1))) => {
+            assert_eq!(("args", Capability::Process), (name.as_str(), capability));
+        }
+        _ => panic!("unexpected result"),
+    }
+
+    lox.interpreter_mut().allow(Capability::Process);
+    assert!(lox.run("args();").is_ok());
+
+    for program in ["import \"lib.lox\";", "from \"lib.lox\" import answer;"] {
+        match lox.run(program) {
+            Err(LoxError::Runtime(RuntimeError::CapabilityDenied(name, capability, 1))) => {
+                assert_eq!(("import", Capability::Fs), (name.as_str(), capability));
+            }
+            _ => panic!("unexpected result"),
+        }
+    }
+}
+
+#[test]
+fn deterministic_runs() {
+    struct StepClock(Cell<f64>);
+
+    impl TimeSource for StepClock {
+        fn now(&self) -> f64 {
+            self.0.set(self.0.get() + 1.0);
+            self.0.get()
+        }
+    }
+
+    let program = "[clock(), random(), random_int(1, 6), random_int(1, 6)];";
+    let run = |seed| {
+        let mut lox = Lox::new();
+        lox.interpreter_mut().set_deterministic(seed);
+        lox.run(program).unwrap().to_string()
+    };
+
+    assert_eq!(run(1), run(1));
+    assert_ne!(run(1), run(2));
+    assert!(run(1).starts_with("[0, "));
+
+    let mut lox = Lox::new();
+    lox.interpreter_mut()
+        .set_time_source(StepClock(Cell::new(100.0)));
+    assert_eq!(Value::Number(203.0), lox.run("clock() + clock();").unwrap());
+}
+
+#[test]
+fn evaluate_rules_against_host_data() {
+    let mut lox = Lox::new();
+    lox.run("fun discount(total) { return total * 0.9; }")
+        .unwrap();
+
+    let rule = "discount(price * qty) > 100";
+    for (price, qty, expected) in [(20.0, 6.0, true), (10.0, 5.0, false)] {
+        let bindings = [("price", price.into()), ("qty", qty.into())];
+        let result = lox.interpreter_mut().eval_expr(rule, &bindings);
+        assert_eq!(Value::Bool(expected), result.unwrap());
+    }
+
+    match lox.interpreter_mut().eval_expr("1 + 2 3", &[]) {
+        Err(LoxError::Parse(errors)) => {
+            assert_eq!(
+                "Expect end of expression but got '3' on line 1.",
+                errors[0].to_string()
+            );
+        }
+        _ => panic!("unexpected result"),
+    }
+}
+
+#[test]
+fn call_script_handler_from_host() {
+    let mut lox = Lox::new();
+    lox.run(
+        "var count = 0;\nfun handler(req) { count = count + 1; return req[\"path\"] + \"!\"; }",
+    )
+    .unwrap();
+
+    let handler = lox.interpreter().get_global("handler").unwrap();
+    for path in ["/a", "/b"] {
+        let request = HashMap::from([("path".to_string(), path)]).into();
+        let response = lox.interpreter_mut().call(&handler, &[request]).unwrap();
+        assert_eq!(Value::String(format!("{path}!")), response);
+    }
+    assert_eq!(
+        Some(Value::Number(2.0)),
+        lox.interpreter().get_global("count")
+    );
+
+    match lox.interpreter_mut().call(&handler, &[Value::Nil]) {
+        Err(RuntimeError::ValueNotIndexable(Value::Nil, 2)) => {}
+        _ => panic!("unexpected result"),
+    }
+}